@@ -0,0 +1,479 @@
+use std::{
+    fmt::{self, Debug, Display},
+    fs, io,
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Utc};
+use cookie::Cookie;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroize;
+
+use crate::{
+    credential::Account,
+    model,
+    telemetry::REDACTED,
+    user::get_user_status::{self, GetUserStatus},
+    Client,
+};
+
+/// names of the session cookies of Naver, in the order of the fields of [`Auth`]
+pub(crate) const COOKIE_NAMES: [&str; 3] = ["NID_SES", "NID_AUT", "NID_JKL"];
+
+/// Session cookies of a Naver account.
+///
+/// the values are [`Secret`]s, so `Debug` never prints them. `Auth` can be deserialized,
+/// but only serialized explicitly through [`Auth::expose`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Auth {
+    pub nid_ses: Secret,
+    pub nid_aut: Secret,
+    pub nid_jkl: Secret,
+}
+
+/// Secret string which is redacted by `Debug` and `Display`, and zeroized when dropped.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_owned())
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Serializes the values of [`Auth`] in plain text, see [`Auth::expose`].
+#[derive(Serialize)]
+pub struct ExposedAuth<'a> {
+    nid_ses: &'a str,
+    nid_aut: &'a str,
+    nid_jkl: &'a str,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("missing cookie {0}")]
+    Missing(&'static str),
+
+    #[error("expired cookie {0}")]
+    Expired(&'static str),
+
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("deserialize json: {0}")]
+    DeserializeJson(#[from] serde_json::Error),
+
+    #[error("unsupported cookie export format")]
+    UnsupportedFormat,
+
+    #[error("not logged in")]
+    LoggedOut,
+
+    #[error("get user status: {0}")]
+    UserStatus(#[from] get_user_status::Error),
+}
+
+/// Event emitted when Naver rotates session cookies of an [`Account`],
+/// see [`ClientBuilder::on_auth_rotated`](crate::client::ClientBuilder::on_auth_rotated).
+#[derive(Debug, Clone)]
+pub struct AuthRotated {
+    /// the updated auth
+    pub auth: Auth,
+    /// names of the rotated cookies, e.g. `NID_SES`
+    pub cookies: Vec<&'static str>,
+}
+
+pub(crate) type OnAuthRotated = Arc<dyn Fn(&AuthRotated) + Send + Sync>;
+
+/// name and value of a session cookie set by a `Set-Cookie` header value,
+/// `None` for other cookies and removals.
+pub(crate) fn parse_set_cookie(set_cookie: &str) -> Option<(&'static str, &str)> {
+    let mut attributes = set_cookie.split(';');
+
+    let (name, value) = attributes.next()?.split_once('=')?;
+    let name = COOKIE_NAMES.into_iter().find(|x| *x == name.trim())?;
+    let value = value.trim();
+
+    let removed = value.is_empty()
+        || attributes.any(|attribute| {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let (key, value) = (key.trim(), value.trim());
+
+            if key.eq_ignore_ascii_case("max-age") {
+                value.parse::<i64>().is_ok_and(|x| x <= 0)
+            } else if key.eq_ignore_ascii_case("expires") {
+                DateTime::parse_from_rfc2822(value).is_ok_and(|x| x <= Utc::now())
+            } else {
+                false
+            }
+        });
+
+    (!removed).then_some((name, value))
+}
+
+/// cookie read from a browser export
+struct ExportedCookie {
+    name: String,
+    value: String,
+    domain: Option<String>,
+    /// seconds since the unix epoch, `None` for session cookies
+    expires: Option<f64>,
+}
+
+impl Auth {
+    pub fn new(
+        nid_ses: impl Into<Secret>,
+        nid_aut: impl Into<Secret>,
+        nid_jkl: impl Into<Secret>,
+    ) -> Self {
+        Self {
+            nid_ses: nid_ses.into(),
+            nid_aut: nid_aut.into(),
+            nid_jkl: nid_jkl.into(),
+        }
+    }
+
+    /// value of the `Cookie` header
+    pub(crate) fn to_cookie(&self) -> String {
+        let Auth {
+            nid_ses,
+            nid_aut,
+            nid_jkl,
+        } = self;
+
+        Cookie::from_iter([
+            ("NID_SES", nid_ses.expose()),
+            ("NID_AUT", nid_aut.expose()),
+            ("NID_JKL", nid_jkl.expose()),
+        ])
+        .into_str()
+    }
+
+    /// opts in to serialize the cookies in plain text, e.g. to persist them.
+    ///
+    /// ```ignore
+    /// let json = serde_json::to_string(&auth.expose())?;
+    /// let auth: Auth = serde_json::from_str(&json)?;
+    /// ```
+    pub fn expose(&self) -> ExposedAuth<'_> {
+        ExposedAuth {
+            nid_ses: self.nid_ses.expose(),
+            nid_aut: self.nid_aut.expose(),
+            nid_jkl: self.nid_jkl.expose(),
+        }
+    }
+
+    /// checks the session is logged in with [`GetUserStatus`], returning the status of its user.
+    ///
+    /// sent without a [`Client`], see [`Auth::validate_with`].
+    pub async fn validate(&self) -> Result<model::UserStatus, AuthError> {
+        Self::logged_in(GetUserStatus.send(self).await?)
+    }
+
+    /// like [`Auth::validate`], but sent by `client` with its base urls, proxy, headers
+    /// and middlewares. the client's credentials are not used.
+    pub async fn validate_with(&self, client: &Client) -> Result<model::UserStatus, AuthError> {
+        let account = Account::new(self.clone());
+
+        Self::logged_in(client.execute_as(GetUserStatus, account).await?)
+    }
+
+    fn logged_in(user_status: model::UserStatus) -> Result<model::UserStatus, AuthError> {
+        if !user_status.logged_in {
+            return Err(AuthError::LoggedOut);
+        }
+
+        Ok(user_status)
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn validate_blocking(&self) -> Result<model::UserStatus, AuthError> {
+        crate::blocking::block_on(self.validate())
+    }
+
+    /// parses the value of a `Cookie` header, e.g. `NID_SES=..; NID_AUT=..; NID_JKL=..`.
+    /// a leading `Cookie:` is ignored.
+    pub fn from_cookie_header(header: &str) -> Result<Self, AuthError> {
+        let header = header.trim();
+        let header = header
+            .strip_prefix("Cookie:")
+            .or_else(|| header.strip_prefix("cookie:"))
+            .unwrap_or(header);
+
+        let cookies = header.split(';').filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+
+            Some(ExportedCookie {
+                name: name.trim().to_owned(),
+                value: value.trim().to_owned(),
+                domain: None,
+                expires: None,
+            })
+        });
+
+        Self::from_exported(cookies)
+    }
+
+    /// parses a Netscape `cookies.txt` file, as exported by curl or browser extensions.
+    pub fn from_netscape(cookies_txt: &str) -> Result<Self, AuthError> {
+        let cookies = cookies_txt.lines().filter_map(|line| {
+            let line = line.trim_end_matches('\r');
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+
+            if line.starts_with('#') || line.trim().is_empty() {
+                return None;
+            }
+
+            // domain, include subdomains, path, secure, expires, name, value
+            let fields = line.split('\t').collect::<Vec<_>>();
+
+            let [domain, _, _, _, expires, name, value] = fields[..] else {
+                return None;
+            };
+
+            Some(ExportedCookie {
+                name: name.to_owned(),
+                value: value.to_owned(),
+                domain: Some(domain.to_owned()),
+                expires: expires.parse().ok().filter(|x| *x > 0.0),
+            })
+        });
+
+        Self::from_exported(cookies)
+    }
+
+    pub fn from_netscape_file(path: impl AsRef<Path>) -> Result<Self, AuthError> {
+        Self::from_netscape(&fs::read_to_string(path)?)
+    }
+
+    /// parses a JSON cookie export.
+    ///
+    /// supports an array of cookies, as exported by EditThisCookie, Cookie-Editor, Puppeteer and
+    /// Selenium, and an object with a `cookies` array like the storage state of Playwright.
+    /// expiration is read from `expirationDate`, `expires` or `expiry`.
+    pub fn from_json(json: &str) -> Result<Self, AuthError> {
+        let value: Value = serde_json::from_str(json)?;
+
+        let cookies = match &value {
+            Value::Array(cookies) => cookies,
+            Value::Object(object) => match object.get("cookies") {
+                Some(Value::Array(cookies)) => cookies,
+                _ => return Err(AuthError::UnsupportedFormat),
+            },
+            _ => return Err(AuthError::UnsupportedFormat),
+        };
+
+        let cookies = cookies.iter().filter_map(|cookie| {
+            let name = cookie.get("name")?.as_str()?;
+            let value = cookie.get("value")?.as_str()?;
+
+            let session = cookie.get("session").and_then(Value::as_bool) == Some(true);
+            let expires = ["expirationDate", "expires", "expiry"]
+                .into_iter()
+                .find_map(|key| cookie.get(key)?.as_f64())
+                .filter(|x| !session && *x > 0.0);
+
+            Some(ExportedCookie {
+                name: name.to_owned(),
+                value: value.to_owned(),
+                domain: cookie.get("domain").and_then(Value::as_str).map(Into::into),
+                expires,
+            })
+        });
+
+        Self::from_exported(cookies)
+    }
+
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, AuthError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// picks the session cookies of Naver. a cookie which is set several times is
+    /// only considered expired if every one of them is.
+    fn from_exported(cookies: impl IntoIterator<Item = ExportedCookie>) -> Result<Self, AuthError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        let mut found: [Option<Result<String, ()>>; 3] = Default::default();
+
+        for cookie in cookies {
+            let is_naver = cookie.domain.as_deref().is_none_or(|domain| {
+                let domain = domain.trim_start_matches('.');
+
+                domain == "naver.com" || domain.ends_with(".naver.com")
+            });
+
+            let Some(i) = COOKIE_NAMES.iter().position(|x| *x == cookie.name) else {
+                continue;
+            };
+
+            if !is_naver || cookie.value.is_empty() {
+                continue;
+            }
+
+            if cookie.expires.is_some_and(|expires| expires <= now) {
+                found[i].get_or_insert(Err(()));
+            } else {
+                found[i] = Some(Ok(cookie.value));
+            }
+        }
+
+        let [nid_ses, nid_aut, nid_jkl] = found;
+
+        let value = |cookie: Option<Result<String, ()>>, name| match cookie {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(())) => Err(AuthError::Expired(name)),
+            None => Err(AuthError::Missing(name)),
+        };
+
+        Ok(Self::new(
+            value(nid_ses, "NID_SES")?,
+            value(nid_aut, "NID_AUT")?,
+            value(nid_jkl, "NID_JKL")?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_auth(auth: Auth) {
+        assert_eq!(auth, Auth::new("ses", "aut", "jkl"));
+    }
+
+    #[test]
+    fn test_set_cookie() {
+        assert_eq!(
+            parse_set_cookie("NID_SES=ses; Path=/; Domain=.naver.com; HttpOnly"),
+            Some(("NID_SES", "ses"))
+        );
+        assert_eq!(parse_set_cookie("NID_JKL=; Max-Age=0"), None);
+        assert_eq!(
+            parse_set_cookie("NID_AUT=aut; Expires=Thu, 01 Jan 1970 00:00:00 GMT"),
+            None
+        );
+        assert_eq!(parse_set_cookie("other=1"), None);
+    }
+
+    #[test]
+    fn test_secret() {
+        let auth = Auth::new("secret1", "secret2", "secret3");
+
+        let debug = format!("{auth:?}");
+        assert!(!debug.contains("secret"));
+        assert_eq!(auth.nid_ses.to_string(), REDACTED);
+
+        let json = serde_json::to_string(&auth.expose()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nid_ses":"secret1","nid_aut":"secret2","nid_jkl":"secret3"}"#
+        );
+
+        let deserialized: Auth = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, auth);
+    }
+
+    #[test]
+    fn test_cookie_header() {
+        assert_auth(
+            Auth::from_cookie_header("Cookie: NID_AUT=aut; other=1; NID_SES=ses; NID_JKL=jkl")
+                .unwrap(),
+        );
+
+        assert!(matches!(
+            Auth::from_cookie_header("NID_AUT=aut; NID_SES=ses"),
+            Err(AuthError::Missing("NID_JKL"))
+        ));
+    }
+
+    #[test]
+    fn test_netscape() {
+        let cookies_txt = "\
+# Netscape HTTP Cookie File
+.naver.com\tTRUE\t/\tFALSE\t0\tNID_SES\tses
+#HttpOnly_.naver.com\tTRUE\t/\tTRUE\t4102444800\tNID_AUT\taut
+.naver.com\tTRUE\t/\tFALSE\t0\tNID_JKL\tjkl
+.example.com\tTRUE\t/\tFALSE\t0\tNID_JKL\tother
+.evilnaver.com\tTRUE\t/\tFALSE\t0\tNID_JKL\tother
+";
+
+        assert_auth(Auth::from_netscape(cookies_txt).unwrap());
+
+        let expired = cookies_txt.replace("4102444800", "946684800");
+
+        assert!(matches!(
+            Auth::from_netscape(&expired),
+            Err(AuthError::Expired("NID_AUT"))
+        ));
+    }
+
+    #[test]
+    fn test_json() {
+        // EditThisCookie / Cookie-Editor
+        let json = r#"[
+            {"domain": ".naver.com", "name": "NID_SES", "value": "ses", "session": true},
+            {"domain": ".naver.com", "name": "NID_AUT", "value": "aut", "expirationDate": 4102444800.5},
+            {"domain": ".naver.com", "name": "NID_JKL", "value": "jkl", "session": true}
+        ]"#;
+
+        assert_auth(Auth::from_json(json).unwrap());
+
+        // Playwright storage state
+        let json = r#"{"cookies": [
+            {"name": "NID_SES", "value": "ses", "domain": ".naver.com", "expires": -1},
+            {"name": "NID_AUT", "value": "aut", "domain": ".naver.com", "expires": 946684800},
+            {"name": "NID_JKL", "value": "jkl", "domain": ".naver.com", "expires": -1}
+        ], "origins": []}"#;
+
+        assert!(matches!(
+            Auth::from_json(json),
+            Err(AuthError::Expired("NID_AUT"))
+        ));
+
+        assert!(matches!(
+            Auth::from_json("{}"),
+            Err(AuthError::UnsupportedFormat)
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+use futures_util::{stream, Stream, StreamExt};
+
+use crate::{
+    live::{
+        get_live_detail, get_live_detail::GetLiveDetail, get_live_status,
+        get_live_status::GetLiveStatus,
+    },
+    model,
+    request::{Decoder, Encode, EncodeError, EndpointError, Request},
+    response::Response,
+    Client,
+};
+
+impl Client {
+    /// fetches the live status of every channel, running at most `concurrency` requests at once.
+    ///
+    /// results are yielded in completion order with their channel id. requests go through the
+    /// client's rate limiter, retry policy and cache like [`Client::execute`].
+    pub fn get_live_status_batch<I>(
+        &self,
+        channel_ids: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = (String, Result<model::LiveStatus, get_live_status::Error>)> + Send + 'static
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        I::IntoIter: Send + 'static,
+    {
+        self.execute_batch(
+            channel_ids,
+            concurrency,
+            |channel_id| GetLiveStatus { channel_id }.encode(),
+            Decoder::of::<GetLiveStatus>(),
+        )
+    }
+
+    /// fetches the live detail of every channel, see [`Client::get_live_status_batch`].
+    pub fn get_live_detail_batch<I>(
+        &self,
+        channel_ids: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = (String, Result<model::LiveDetail, get_live_detail::Error>)> + Send + 'static
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        I::IntoIter: Send + 'static,
+    {
+        self.execute_batch(
+            channel_ids,
+            concurrency,
+            |channel_id| GetLiveDetail { channel_id }.encode(),
+            Decoder::of::<GetLiveDetail>(),
+        )
+    }
+
+    /// executes the endpoint encoded by `encode` for every channel. the endpoint is encoded
+    /// eagerly, so it may borrow the channel id.
+    fn execute_batch<I, O, Er>(
+        &self,
+        channel_ids: I,
+        concurrency: usize,
+        encode: fn(&str) -> Result<Request, EncodeError>,
+        decoder: Decoder<O, Er>,
+    ) -> impl Stream<Item = (String, Result<O, Er>)> + Send + 'static
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        I::IntoIter: Send + 'static,
+        O: Send + 'static,
+        Er: EndpointError,
+    {
+        let client = self.clone();
+
+        stream::iter(channel_ids)
+            .map(move |channel_id| {
+                let client = client.clone();
+                let channel_id = channel_id.into();
+                let request = encode(&channel_id);
+
+                async move {
+                    let r = match request {
+                        Ok(request) => client
+                            .execute_request(request, decoder)
+                            .await
+                            .map(Response::into_content),
+                        Err(err) => Err(err.into()),
+                    };
+
+                    (channel_id, r)
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{Respond, LIVE_DETAIL, LIVE_STATUS};
+
+    #[tokio::test]
+    async fn test_batch() {
+        let client = Client::builder()
+            .middleware(Respond::ok(LIVE_STATUS).not_found("unknown"))
+            .build()
+            .unwrap();
+
+        let channel_ids = (0..100)
+            .map(|i| format!("channel-{i}"))
+            .chain(["unknown".to_owned()])
+            .collect::<Vec<_>>();
+
+        let results = client
+            .get_live_status_batch(channel_ids, 8)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(results.len(), 101);
+
+        for (channel_id, r) in results {
+            if channel_id == "unknown" {
+                assert!(r.is_err());
+            } else {
+                assert_eq!(r.unwrap().live_title, "title");
+            }
+        }
+
+        let client = Client::builder()
+            .middleware(Respond::ok(LIVE_DETAIL))
+            .build()
+            .unwrap();
+
+        let results = client
+            .get_live_detail_batch(["a", "b"], 2)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .into_iter()
+            .all(|(_, r)| r.unwrap().inherit.live_id == 1234567));
+    }
+}
@@ -0,0 +1,95 @@
+//! Synchronous API, sharing [`Encode`](crate::request::Encode) and [`Decode`](crate::request::Decode)
+//! with the asynchronous one.
+//!
+//! requests are driven by a runtime owned by this module, so the functions here must not be
+//! called from within an asynchronous runtime.
+
+use std::{future::Future, sync::OnceLock};
+
+use tokio::runtime::Runtime;
+
+use crate::request::Endpoint;
+
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to initialize runtime")
+        })
+        .block_on(future)
+}
+
+/// Blocking [`Client`](crate::Client).
+///
+/// build it with [`ClientBuilder::build_blocking`](crate::client::ClientBuilder::build_blocking) or
+/// [`Client::new`].
+#[derive(Clone, Default)]
+pub struct Client {
+    inner: crate::Client,
+}
+
+/// the blocking client takes over the connection pool of `inner`, which is bound to the runtime
+/// that opened its connections. an asynchronous client that is also used from another runtime
+/// must not be converted, or requests may hang on connections of that runtime.
+impl From<crate::Client> for Client {
+    fn from(inner: crate::Client) -> Self {
+        Self { inner }
+    }
+}
+
+impl Client {
+    /// # Panics
+    ///
+    /// if the underlying HTTP client cannot be initialized.
+    pub fn new() -> Self {
+        crate::Client::builder()
+            .build_blocking()
+            .expect("failed to initialize http client")
+    }
+
+    /// the returned client shares the connection pool of this one,
+    /// don't send requests with it from another runtime.
+    pub fn as_async(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    pub fn execute<E: Endpoint>(&self, endpoint: E) -> Result<E::Output, E::Error> {
+        block_on(self.inner.execute(endpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        fixtures::Respond,
+        live::{get_live_status, get_live_status::GetLiveStatus},
+    };
+
+    #[test]
+    fn test_blocking() {
+        let client = crate::Client::builder()
+            .middleware(Respond::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "service unavailable",
+            ))
+            .build_blocking()
+            .unwrap();
+
+        let err = client
+            .execute(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            get_live_status::Error::Undefined(StatusCode::SERVICE_UNAVAILABLE, _)
+        ));
+    }
+}
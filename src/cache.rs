@@ -0,0 +1,310 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+
+use crate::rate_limit::RateLimitHint;
+
+/// Configuration of [`Cache`].
+///
+/// endpoints are identified by their [`Endpoint::PATH`](crate::request::Endpoint::PATH) template.
+/// responses without a TTL are only cached when they have an `ETag`, and are revalidated
+/// with `If-None-Match` every time.
+#[derive(Debug, Clone, Default)]
+pub struct CachePolicy {
+    /// used for endpoints which are not listed in `endpoints`
+    pub default_ttl: Option<Duration>,
+    pub endpoints: HashMap<&'static str, Duration>,
+    /// uses the minimum interval of [`RateLimitHint`]s as TTL, e.g. `callPeriodMilliSecond` of the live status
+    pub respect_hints: bool,
+}
+
+impl CachePolicy {
+    fn ttl(&self, endpoint: &'static str, hint: Option<RateLimitHint>) -> Option<Duration> {
+        hint.and_then(|hint| hint.min_interval)
+            .filter(|_| self.respect_hints)
+            .or_else(|| self.endpoints.get(endpoint).copied())
+            .or(self.default_ttl)
+    }
+}
+
+/// Successful response stored in a [`CacheStorage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    pub expires_at: SystemTime,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at > SystemTime::now()
+    }
+
+    pub fn etag(&self) -> Option<&HeaderValue> {
+        self.headers.get(header::ETAG)
+    }
+
+    pub(crate) fn to_response(&self) -> http::Response<Bytes> {
+        let mut response = http::Response::new(self.body.clone());
+
+        *response.status_mut() = StatusCode::OK;
+        *response.headers_mut() = self.headers.clone();
+
+        response
+    }
+}
+
+/// Storage of [`Cache`], keyed by `GET /path?query`, followed by ` account=<id>`
+/// for requests sent with an account.
+pub trait CacheStorage: Send + Sync + 'static {
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+
+    fn set(&self, key: String, entry: CacheEntry);
+
+    fn remove(&self, key: &str);
+}
+
+/// In-memory [`CacheStorage`]. once the storage holds `max_entries`, expired entries
+/// which can't be revalidated are evicted first, then the least recently used entry.
+pub struct MemoryStorage {
+    max_entries: usize,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    /// key -> entry and the tick it was last used at
+    entries: HashMap<String, (CacheEntry, u64)>,
+    tick: u64,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+impl MemoryStorage {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            state: Mutex::default(),
+        }
+    }
+}
+
+impl CacheStorage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let mut state = self.state.lock().unwrap();
+        let MemoryState { entries, tick } = &mut *state;
+
+        let (entry, used) = entries.get_mut(key)?;
+
+        *tick += 1;
+        *used = *tick;
+
+        Some(entry.clone())
+    }
+
+    fn set(&self, key: String, entry: CacheEntry) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let MemoryState { entries, tick } = &mut *state;
+
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (entry, _)| entry.is_fresh() || entry.etag().is_some());
+
+            if entries.len() >= self.max_entries {
+                let lru = entries
+                    .iter()
+                    .min_by_key(|(_, (_, used))| *used)
+                    .map(|(key, _)| key.clone());
+
+                if let Some(lru) = lru {
+                    entries.remove(&lru);
+                }
+            }
+        }
+
+        *tick += 1;
+        entries.insert(key, (entry, *tick));
+    }
+
+    fn remove(&self, key: &str) {
+        self.state.lock().unwrap().entries.remove(key);
+    }
+}
+
+/// Response cache of [`Client`](crate::Client), only `GET` requests are cached.
+///
+/// responses of requests sent with an account are only served to the same account.
+#[derive(Clone)]
+pub struct Cache {
+    policy: Arc<CachePolicy>,
+    storage: Arc<dyn CacheStorage>,
+}
+
+impl Cache {
+    /// caches in a [`MemoryStorage`]
+    pub fn new(policy: CachePolicy) -> Self {
+        Self::with_storage(policy, MemoryStorage::default())
+    }
+
+    pub fn with_storage(policy: CachePolicy, storage: impl CacheStorage) -> Self {
+        Self {
+            policy: Arc::new(policy),
+            storage: Arc::new(storage),
+        }
+    }
+
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    pub fn storage(&self) -> &dyn CacheStorage {
+        &*self.storage
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.storage.get(key)
+    }
+
+    pub(crate) fn store(
+        &self,
+        endpoint: &'static str,
+        key: &str,
+        headers: HeaderMap,
+        body: Bytes,
+        hint: Option<RateLimitHint>,
+    ) {
+        let ttl = self.policy.ttl(endpoint, hint);
+
+        if ttl.is_none() && !headers.contains_key(header::ETAG) {
+            return;
+        }
+
+        let entry = CacheEntry {
+            headers,
+            body,
+            expires_at: SystemTime::now() + ttl.unwrap_or_default(),
+        };
+
+        self.storage.set(key.to_owned(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        fixtures::LIVE_STATUS, live::get_live_status::GetLiveStatus, middleware::Middleware,
+        request::Request, Client,
+    };
+
+    /// responds the live status with an `ETag`, or `304` if it matches `If-None-Match`.
+    #[derive(Default)]
+    struct Server {
+        sent: AtomicUsize,
+        not_modified: AtomicUsize,
+    }
+
+    impl Middleware for Arc<Server> {
+        fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+
+            let etag = HeaderValue::from_static("\"1\"");
+            let mut response = http::Response::new(Bytes::from_static(LIVE_STATUS.as_bytes()));
+
+            if request.headers().and_then(|x| x.get(header::IF_NONE_MATCH)) == Some(&etag) {
+                self.not_modified.fetch_add(1, Ordering::Relaxed);
+
+                *response.status_mut() = StatusCode::NOT_MODIFIED;
+                *response.body_mut() = Bytes::new();
+            }
+
+            response.headers_mut().insert(header::ETAG, etag);
+
+            Some(response)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache() {
+        let server = Arc::new(Server::default());
+
+        let client = Client::builder()
+            .middleware(server.clone())
+            .cache(Cache::new(CachePolicy {
+                respect_hints: true,
+                ..Default::default()
+            }))
+            .build()
+            .unwrap();
+
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+
+        // cached for `callPeriodMilliSecond`
+        for _ in 0..3 {
+            let live_status = client.execute(endpoint.clone()).await.unwrap();
+            assert_eq!(live_status.live_title, "title");
+        }
+
+        assert_eq!(server.sent.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        let storage = MemoryStorage::new(2);
+
+        let entry = |etag: &'static str| CacheEntry {
+            headers: HeaderMap::from_iter([(header::ETAG, HeaderValue::from_static(etag))]),
+            body: Bytes::new(),
+            expires_at: SystemTime::now() + Duration::from_secs(60),
+        };
+
+        storage.set("a".into(), entry("\"a\""));
+        storage.set("b".into(), entry("\"b\""));
+        storage.get("a");
+        storage.set("c".into(), entry("\"c\""));
+
+        // `b` is the least recently used
+        assert!(storage.get("a").is_some());
+        assert!(storage.get("b").is_none());
+        assert!(storage.get("c").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revalidate() {
+        let server = Arc::new(Server::default());
+
+        let client = Client::builder()
+            .middleware(server.clone())
+            .cache(Cache::new(CachePolicy::default()))
+            .build()
+            .unwrap();
+
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+
+        for _ in 0..3 {
+            let live_status = client.execute(endpoint.clone()).await.unwrap();
+            assert_eq!(live_status.live_title, "title");
+        }
+
+        assert_eq!(server.sent.load(Ordering::Relaxed), 3);
+        assert_eq!(server.not_modified.load(Ordering::Relaxed), 2);
+    }
+}
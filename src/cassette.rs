@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bytes::Bytes;
+use http::{HeaderName, HeaderValue, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{middleware::Middleware, request::Request};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// sends every request and overwrites the cassette with the interactions
+    Record,
+    /// never sends a request. requests which were not recorded fail with `501 Not Implemented`
+    Replay,
+    /// replays recorded requests, and sends and records the others
+    ReplayOrRecord,
+}
+
+/// Records request/response pairs into a JSON file and replays them.
+///
+/// added with [`ClientBuilder::cassette`](crate::client::ClientBuilder::cassette). requests are
+/// matched by method, path and query. a request recorded several times is replayed in the
+/// recorded order, repeating the last response. credentials are never recorded.
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    /// request key -> number of times replayed
+    replayed: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RecordedRequest {
+    fn new(request: &Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            path: request.path().to_owned(),
+            query: request.query().filter(|x| !x.is_empty()).map(Into::into),
+        }
+    }
+
+    fn key(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{} {}?{}", self.method, self.path, query),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn new(response: &http::Response<Bytes>) -> Self {
+        Self {
+            status: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .filter(|(name, _)| *name != http::header::SET_COOKIE)
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+                .collect(),
+            body: String::from_utf8_lossy(response.body()).into_owned(),
+        }
+    }
+
+    fn to_response(&self) -> http::Response<Bytes> {
+        let mut response = http::Response::new(Bytes::from(self.body.clone()));
+
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value.as_str()),
+            ) {
+                response.headers_mut().append(name, value);
+            }
+        }
+
+        response
+    }
+}
+
+impl Cassette {
+    /// reads the cassette at `path` unless `mode` is [`CassetteMode::Record`].
+    pub fn open(path: impl AsRef<Path>, mode: CassetteMode) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+
+        let interactions = match mode {
+            CassetteMode::Record => Vec::new(),
+            CassetteMode::Replay => Self::read(&path)?,
+            CassetteMode::ReplayOrRecord => match Self::read(&path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+                r => r?,
+            },
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            state: Mutex::new(State {
+                interactions,
+                ..Default::default()
+            }),
+        })
+    }
+
+    pub fn record(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open(path, CassetteMode::Record)
+    }
+
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open(path, CassetteMode::Replay)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    fn read(path: &Path) -> io::Result<Vec<Interaction>> {
+        let file: CassetteFile = serde_json::from_slice(&fs::read(path)?)?;
+
+        Ok(file.interactions)
+    }
+
+    fn write(&self, interactions: &[Interaction]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = CassetteFile {
+            interactions: interactions.to_vec(),
+        };
+
+        fs::write(&self.path, serde_json::to_vec_pretty(&file)?)
+    }
+}
+
+impl Middleware for Cassette {
+    fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+        if self.mode == CassetteMode::Record {
+            return None;
+        }
+
+        let key = RecordedRequest::new(request).key();
+        let mut state = self.state.lock().unwrap();
+
+        let recorded = state
+            .interactions
+            .iter()
+            .filter(|x| x.request.key() == key)
+            .map(|x| x.response.clone())
+            .collect::<Vec<_>>();
+
+        if recorded.is_empty() {
+            if self.mode == CassetteMode::ReplayOrRecord {
+                return None;
+            }
+
+            let mut response = http::Response::new(Bytes::from(format!(
+                "cassette {}: no interaction recorded for {key}",
+                self.path.display()
+            )));
+            *response.status_mut() = StatusCode::NOT_IMPLEMENTED;
+
+            return Some(response);
+        }
+
+        let replayed = state.replayed.entry(key).or_default();
+        let response = &recorded[(*replayed).min(recorded.len() - 1)];
+
+        *replayed += 1;
+
+        Some(response.to_response())
+    }
+
+    fn after_response(&self, request: &Request, response: &mut http::Response<Bytes>) {
+        let request = RecordedRequest::new(request);
+        let mut state = self.state.lock().unwrap();
+
+        let replayed = state.replayed.contains_key(&request.key());
+
+        if self.mode == CassetteMode::Replay || replayed {
+            return;
+        }
+
+        state.interactions.push(Interaction {
+            request,
+            response: RecordedResponse::new(response),
+        });
+
+        if let Err(err) = self.write(&state.interactions) {
+            tracing::error!("cassette {}: {err}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::{
+        fixtures::{Respond, LIVE_CASSETTE, LIVE_STATUS},
+        live::{get_live_detail::GetLiveDetail, get_live_status, get_live_status::GetLiveStatus},
+        model::LiveStatusType,
+        Client,
+    };
+
+    #[tokio::test]
+    async fn test_replay() {
+        let client = Client::builder()
+            .cassette(Cassette::replay(LIVE_CASSETTE).unwrap())
+            .build()
+            .unwrap();
+
+        let live_status = client
+            .execute(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_status.status, LiveStatusType::Open);
+        assert_eq!(live_status.live_polling_status.call_period_ms, 10000);
+
+        let live_detail = client
+            .execute(GetLiveDetail {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_detail.inherit.live_id, 1234567);
+        assert_eq!(
+            live_detail.inherit.channel.channel_id,
+            "475313e6c26639d5763628313b4c130e"
+        );
+
+        let err = client
+            .execute(GetLiveStatus {
+                channel_id: "unknown",
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            get_live_status::Error::Undefined(StatusCode::NOT_IMPLEMENTED, _)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("chzzk-cassette-{nanos}.json"));
+
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+
+        let client = Client::builder()
+            .cassette(Cassette::record(&path).unwrap())
+            .middleware(Respond::ok(LIVE_STATUS))
+            .build()
+            .unwrap();
+
+        let recorded = client.execute(endpoint.clone()).await.unwrap();
+
+        let client = Client::builder()
+            .cassette(Cassette::replay(&path).unwrap())
+            .build()
+            .unwrap();
+
+        let replayed = client.execute(endpoint).await.unwrap();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(recorded, replayed);
+    }
+}
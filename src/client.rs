@@ -0,0 +1,765 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use tracing::Instrument;
+
+use crate::{
+    auth::{AuthRotated, OnAuthRotated},
+    cache::Cache,
+    cassette::Cassette,
+    credential::{Account, CredentialProvider, StaticCredentials},
+    middleware::Middleware,
+    rate_limit::RateLimiter,
+    request::{into_http_response, Api, Auth, Decoder, Endpoint, EndpointError, Request},
+    response::Response,
+    retry::{self, RetryPolicy},
+    single_flight::SingleFlight,
+    telemetry::{self, Call},
+    CHZZK_API_URL, GAME_API_URL,
+};
+
+pub use reqwest::Proxy;
+
+pub const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36";
+pub const CHZZK_URL: &str = "https://chzzk.naver.com";
+
+/// Reusable Chzzk client.
+///
+/// Owns a pooled HTTP client, so connections and TLS sessions are shared
+/// between calls. Cloning is cheap and clones share the same pool.
+///
+/// every call runs in a `chzzk_request` tracing span carrying the endpoint name, path,
+/// HTTP status, Chzzk code and latency. the `metrics` feature records
+/// `chzzk_requests_total` and `chzzk_request_duration_seconds` per endpoint.
+#[derive(Clone)]
+pub struct Client {
+    inner: Arc<ClientRef>,
+}
+
+struct ClientRef {
+    http: reqwest::Client,
+    base_url: Cow<'static, str>,
+    game_base_url: Cow<'static, str>,
+    /// account set with [`ClientBuilder::auth`]
+    account: Option<Arc<Account>>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    on_auth_rotated: Option<OnAuthRotated>,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    cache: Option<Cache>,
+    single_flight: Option<SingleFlight>,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// # Panics
+    ///
+    /// if the underlying HTTP client cannot be initialized. use [`Client::builder`] to handle the error.
+    pub fn new() -> Self {
+        Self::builder()
+            .build()
+            .expect("failed to initialize http client")
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.inner.base_url
+    }
+
+    pub fn game_base_url(&self) -> &str {
+        &self.inner.game_base_url
+    }
+
+    /// base url `request` is sent to
+    fn base_url_of<'a>(&'a self, request: &'a Request) -> &'a str {
+        request.base_url().unwrap_or(match request.api() {
+            Api::Chzzk => self.base_url(),
+            Api::Game => self.game_base_url(),
+        })
+    }
+
+    /// the auth set with [`ClientBuilder::auth`], with the cookies rotated by Naver applied.
+    pub fn auth(&self) -> Option<Auth> {
+        self.inner.account.as_ref().map(|account| account.auth())
+    }
+
+    /// the account set with [`ClientBuilder::auth`], e.g. to replace its cookies with
+    /// [`Account::set_auth`] once it's rejected.
+    pub fn account(&self) -> Option<&Arc<Account>> {
+        self.inner.account.as_ref()
+    }
+
+    pub fn credential_provider(&self) -> Option<&dyn CredentialProvider> {
+        self.inner.credentials.as_deref()
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.inner.retry
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.inner.rate_limiter.as_ref()
+    }
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.inner.cache.as_ref()
+    }
+
+    pub async fn execute<E: Endpoint>(&self, endpoint: E) -> Result<E::Output, E::Error> {
+        self.execute_response(endpoint)
+            .await
+            .map(Response::into_content)
+    }
+
+    /// like [`Client::execute`], but also returns the status, headers, envelope and raw body of the response.
+    pub async fn execute_response<E: Endpoint>(
+        &self,
+        endpoint: E,
+    ) -> Result<Response<E::Output>, E::Error> {
+        let request = endpoint.encode()?;
+
+        self.execute_request(request, Decoder::of::<E>()).await
+    }
+
+    pub(crate) async fn execute_request<O, Er: EndpointError>(
+        &self,
+        request: Request,
+        decoder: Decoder<O, Er>,
+    ) -> Result<Response<O>, Er> {
+        let account = match &self.inner.credentials {
+            Some(credentials) => credentials
+                .credential(&request)
+                .map_err(|error| Er::from_envelope(StatusCode::UNAUTHORIZED, error.into()))?,
+            None => None,
+        };
+
+        self.execute_request_as(request, account, decoder).await
+    }
+
+    /// executes `endpoint` with `account` instead of the one chosen by the credential provider.
+    pub(crate) async fn execute_as<E: Endpoint>(
+        &self,
+        endpoint: E,
+        account: Arc<Account>,
+    ) -> Result<E::Output, E::Error> {
+        let request = endpoint.encode()?;
+
+        self.execute_request_as(request, Some(account), Decoder::of::<E>())
+            .await
+            .map(Response::into_content)
+    }
+
+    async fn execute_request_as<O, Er: EndpointError>(
+        &self,
+        request: Request,
+        account: Option<Arc<Account>>,
+        decoder: Decoder<O, Er>,
+    ) -> Result<Response<O>, Er> {
+        let span = telemetry::span(decoder.name, &request);
+        let started = Instant::now();
+        let mut call = Call::default();
+
+        let r = self
+            .execute_attempts(request, account.as_ref(), decoder, &mut call)
+            .instrument(span.clone())
+            .await;
+
+        call.finish(
+            &span,
+            decoder.name,
+            started.elapsed(),
+            r.as_ref().err().map(|err| err as &dyn Display),
+        );
+
+        r
+    }
+
+    /// executes `request` with the cookies of `account`, cache, rate limit and retries.
+    async fn execute_attempts<O, Er: EndpointError>(
+        &self,
+        mut request: Request,
+        account: Option<&Arc<Account>>,
+        decoder: Decoder<O, Er>,
+        call: &mut Call,
+    ) -> Result<Response<O>, Er> {
+        let key = request.key();
+        let policy = self.retry_policy();
+
+        // responses may differ by account
+        let cache_key = match account {
+            Some(account) => format!("{key} account={:016x}", account.id()),
+            None => key.clone(),
+        };
+        let cache = self.cache().filter(|_| request.method() == Method::GET);
+        let cached = cache.and_then(|cache| cache.get(&cache_key));
+
+        if let Some(cached) = &cached {
+            if cached.is_fresh() {
+                tracing::debug!("cache hit {key}");
+
+                call.cached();
+
+                let envelope = crate::Error::from_slice(&cached.body);
+                let content =
+                    (decoder.decode)(StatusCode::OK, cached.body.clone(), envelope.as_ref())?;
+
+                return Ok(Response::new(
+                    StatusCode::OK,
+                    cached.headers.clone(),
+                    cached.body.clone(),
+                    envelope,
+                    content,
+                ));
+            }
+
+            if let Some(etag) = cached.etag() {
+                request
+                    .headers_mut()
+                    .insert(header::IF_NONE_MATCH, etag.clone());
+            }
+        }
+
+        let mut attempt = 1;
+
+        loop {
+            if let Some(rate_limiter) = self.rate_limiter() {
+                rate_limiter.acquire(decoder.path, &key).await;
+            }
+
+            let mut retry_after = None;
+
+            let r = match self.send_coalesced(request.clone(), account).await {
+                Ok(resp) => {
+                    let mut envelope = crate::Error::from_slice(resp.body());
+
+                    call.response(resp.status(), envelope.as_ref());
+                    retry_after = retry::retry_after(resp.headers());
+
+                    if let Some(account) = account {
+                        account.check(resp.status(), envelope.as_ref());
+                    }
+
+                    let resp = match &cached {
+                        Some(cached) if resp.status() == StatusCode::NOT_MODIFIED => {
+                            tracing::debug!("cache revalidated {key}");
+
+                            envelope = crate::Error::from_slice(&cached.body);
+                            cached.to_response()
+                        }
+                        _ => resp,
+                    };
+
+                    let (parts, body) = resp.into_parts();
+
+                    (decoder.decode)(parts.status, body.clone(), envelope.as_ref()).map(|content| {
+                        Response::new(parts.status, parts.headers, body, envelope, content)
+                    })
+                }
+                Err(err) => Err(err.into()),
+            };
+
+            match r {
+                Err(err) if attempt < policy.max_attempts && err.is_retryable() => {
+                    let backoff = policy.backoff(attempt, retry_after);
+
+                    tracing::debug!("retry after {backoff:?} (attempt {attempt}): {err}");
+
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                r => {
+                    if let Ok(resp) = &r {
+                        let hint = (decoder.rate_limit_hint)(&resp.content);
+
+                        if let (Some(rate_limiter), Some(hint)) = (self.rate_limiter(), hint) {
+                            rate_limiter.hint(decoder.path, &key, hint);
+                        }
+
+                        if let Some(cache) = cache {
+                            cache.store(
+                                decoder.path,
+                                &cache_key,
+                                resp.headers.clone(),
+                                resp.body.clone(),
+                                hint,
+                            );
+                        }
+                    }
+
+                    return r;
+                }
+            }
+        }
+    }
+
+    /// sends `request` once with the cookies of `account`, sharing the response with identical
+    /// `GET` requests of the same account in flight if single-flight is enabled.
+    async fn send_coalesced(
+        &self,
+        request: Request,
+        account: Option<&Arc<Account>>,
+    ) -> reqwest::Result<http::Response<Bytes>> {
+        let single_flight = self
+            .inner
+            .single_flight
+            .as_ref()
+            .filter(|_| request.method() == Method::GET && request.body().is_none());
+
+        match single_flight {
+            Some(single_flight) => {
+                let key = format!(
+                    "{} {} {:?} {:?}",
+                    self.base_url_of(&request),
+                    request.key(),
+                    request.headers(),
+                    account.map(Arc::as_ptr),
+                );
+
+                single_flight.run(key, || self.send(request, account)).await
+            }
+            None => self.send(request, account).await,
+        }
+    }
+
+    /// sends `request` once through the middlewares, with the cookies of `account`.
+    async fn send(
+        &self,
+        mut request: Request,
+        account: Option<&Arc<Account>>,
+    ) -> reqwest::Result<http::Response<Bytes>> {
+        let middlewares = &self.inner.middlewares;
+
+        if let Some(account) = account {
+            if let Ok(mut cookie) = HeaderValue::try_from(account.auth().to_cookie()) {
+                cookie.set_sensitive(true);
+                request.headers_mut().insert(header::COOKIE, cookie);
+            }
+        }
+
+        let short_circuited = middlewares
+            .iter()
+            .find_map(|middleware| middleware.before_send(&mut request));
+
+        let mut resp = match short_circuited {
+            Some(resp) => resp,
+            None => {
+                let base_url = self.base_url_of(&request);
+
+                let resp = request
+                    .clone()
+                    .send_with(&self.inner.http, base_url, None)
+                    .await?;
+
+                into_http_response(resp).await?
+            }
+        };
+
+        for middleware in middlewares.iter().rev() {
+            middleware.after_response(&request, &mut resp);
+        }
+
+        if let Some(account) = account {
+            let rotated = account.apply(resp.headers());
+
+            if let (Some(rotated), Some(on_rotated)) = (rotated, &self.inner.on_auth_rotated) {
+                on_rotated(&rotated);
+            }
+        }
+
+        Ok(resp)
+    }
+}
+
+pub struct ClientBuilder {
+    http: reqwest::ClientBuilder,
+    base_url: Cow<'static, str>,
+    game_base_url: Cow<'static, str>,
+    account: Option<Arc<Account>>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    on_auth_rotated: Option<OnAuthRotated>,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    headers: HeaderMap,
+    cache: Option<Cache>,
+    single_flight: bool,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::builder(),
+            base_url: CHZZK_API_URL.into(),
+            game_base_url: GAME_API_URL.into(),
+            account: None,
+            credentials: None,
+            on_auth_rotated: None,
+            retry: RetryPolicy::none(),
+            rate_limiter: None,
+            middlewares: Vec::new(),
+            headers: HeaderMap::new(),
+            cache: None,
+            single_flight: false,
+        }
+    }
+
+    /// defaults to [`CHZZK_API_URL`]. can be overridden per request with [`WithBaseUrl`](crate::request::WithBaseUrl).
+    pub fn base_url(mut self, base_url: impl Into<Cow<'static, str>>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// base url of endpoints of the game API like [`GetUserStatus`](crate::user::get_user_status::GetUserStatus),
+    /// defaults to [`GAME_API_URL`]. see [`Api`].
+    pub fn game_base_url(mut self, game_base_url: impl Into<Cow<'static, str>>) -> Self {
+        self.game_base_url = game_base_url.into();
+        self
+    }
+
+    /// sends every request with `auth`, see [`StaticCredentials`].
+    /// replaces the credential provider.
+    pub fn auth(mut self, auth: impl Into<Option<Auth>>) -> Self {
+        self.account = auth.into().map(Account::new);
+        self.credentials = self.account.clone().map(|account| {
+            Arc::new(StaticCredentials::new(account)) as Arc<dyn CredentialProvider>
+        });
+        self
+    }
+
+    /// chooses the account of every request with `provider`. replaces the auth.
+    pub fn credential_provider(mut self, provider: impl CredentialProvider) -> Self {
+        self.account = None;
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// called when Naver rotates session cookies of an account with `Set-Cookie`,
+    /// so the updated auth can be persisted. see also [`Client::auth`] and [`Account::auth`].
+    pub fn on_auth_rotated(mut self, f: impl Fn(&AuthRotated) + Send + Sync + 'static) -> Self {
+        self.on_auth_rotated = Some(Arc::new(f));
+        self
+    }
+
+    /// retries are disabled by default.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// pass a clone of the same [`RateLimiter`] to share it between clients.
+    pub fn rate_limiter(mut self, rate_limiter: impl Into<Option<RateLimiter>>) -> Self {
+        self.rate_limiter = rate_limiter.into();
+        self
+    }
+
+    /// pass a clone of the same [`Cache`] to share it between clients.
+    pub fn cache(mut self, cache: impl Into<Option<Cache>>) -> Self {
+        self.cache = cache.into();
+        self
+    }
+
+    /// coalesces identical `GET` requests sent concurrently into a single network call,
+    /// whose response is decoded for every caller. disabled by default.
+    pub fn single_flight(mut self, enabled: bool) -> Self {
+        self.single_flight = enabled;
+        self
+    }
+
+    /// appends a [`Middleware`] applied to every request.
+    pub fn middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// records or replays every request, see [`Cassette`].
+    /// runs before every other middleware.
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.middlewares.insert(0, Arc::new(cassette));
+        self
+    }
+
+    /// total timeout of a request attempt, from connecting until the body has been read.
+    /// can be overridden per request with [`WithTimeout`](crate::request::WithTimeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.timeout(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.connect_timeout(timeout);
+        self
+    }
+
+    /// sent with every request, replacing previously set headers of the same name.
+    /// headers set by an endpoint take precedence.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        let mut last = None;
+
+        for (name, value) in headers {
+            // `None` is another value of the previous name
+            match name {
+                Some(name) => {
+                    self.headers.insert(name.clone(), value);
+                    last = Some(name);
+                }
+                None => {
+                    if let Some(name) = &last {
+                        self.headers.append(name, value);
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// see [`ClientBuilder::default_headers`]
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn user_agent(self, user_agent: HeaderValue) -> Self {
+        self.header(header::USER_AGENT, user_agent)
+    }
+
+    pub fn referer(self, referer: HeaderValue) -> Self {
+        self.header(header::REFERER, referer)
+    }
+
+    pub fn origin(self, origin: HeaderValue) -> Self {
+        self.header(header::ORIGIN, origin)
+    }
+
+    /// sends [`BROWSER_USER_AGENT`], and [`CHZZK_URL`] as `Referer` and `Origin`
+    /// like the Chzzk web client does.
+    pub fn browser_headers(self) -> Self {
+        self.user_agent(HeaderValue::from_static(BROWSER_USER_AGENT))
+            .referer(HeaderValue::from_static(CHZZK_URL))
+            .origin(HeaderValue::from_static(CHZZK_URL))
+    }
+
+    /// adds a HTTP, HTTPS or SOCKS (requires the `socks` feature) proxy.
+    /// proxies from the environment like `HTTPS_PROXY` are used unless one is added.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.http = self.http.proxy(proxy);
+        self
+    }
+
+    /// disables every proxy including the ones from the environment.
+    pub fn no_proxy(mut self) -> Self {
+        self.http = self.http.no_proxy();
+        self
+    }
+
+    pub fn build(self) -> Result<Client, reqwest::Error> {
+        let ClientBuilder {
+            http,
+            base_url,
+            game_base_url,
+            account,
+            credentials,
+            on_auth_rotated,
+            retry,
+            rate_limiter,
+            middlewares,
+            headers,
+            cache,
+            single_flight,
+        } = self;
+
+        Ok(Client {
+            inner: Arc::new(ClientRef {
+                http: http.default_headers(headers).build()?,
+                base_url,
+                game_base_url,
+                account,
+                credentials,
+                on_auth_rotated,
+                retry,
+                rate_limiter,
+                middlewares,
+                cache,
+                single_flight: single_flight.then(SingleFlight::default),
+            }),
+        })
+    }
+
+    /// builds a [`blocking::Client`](crate::blocking::Client) with its own HTTP connection pool.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::Client, reqwest::Error> {
+        self.build().map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::{
+        fixtures::{HttpServer, LIVE_DETAIL, LIVE_STATUS, NOT_FOUND_CHANNEL},
+        live::{get_live_detail::GetLiveDetail, get_live_status, get_live_status::GetLiveStatus},
+        model::LiveStatusType,
+        request::{Encode, Request, WithTimeout},
+    };
+
+    #[tokio::test]
+    async fn test_execute() {
+        let server = HttpServer::start(|head| {
+            if head.contains("/live-detail") {
+                (StatusCode::OK, LIVE_DETAIL)
+            } else {
+                (StatusCode::OK, LIVE_STATUS)
+            }
+        });
+
+        let client = Client::builder()
+            .base_url(server.base_url())
+            .no_proxy()
+            .build()
+            .unwrap();
+
+        let live_status = client
+            .execute(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_status.live_title, "title");
+        assert_eq!(live_status.status, LiveStatusType::Open);
+
+        let live_detail = client
+            .execute(GetLiveDetail {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_detail.inherit.live_id, 1234567);
+        assert_eq!(
+            live_detail.inherit.channel.channel_id,
+            "475313e6c26639d5763628313b4c130e"
+        );
+
+        // the connection is reused
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let client = Client::builder()
+            .base_url(base_url)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        let err = client
+            .execute(WithTimeout {
+                timeout: Duration::from_millis(100),
+                endpoint: GetLiveStatus {
+                    channel_id: "475313e6c26639d5763628313b4c130e",
+                },
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, get_live_status::Error::Timeout));
+    }
+
+    #[test]
+    fn test_default_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::ACCEPT, HeaderValue::from_static("text/html"));
+        headers.append(header::ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("chzzk-rs"));
+
+        let builder = Client::builder()
+            .header(header::ACCEPT, HeaderValue::from_static("*/*"))
+            .default_headers(headers);
+
+        assert_eq!(
+            builder
+                .headers
+                .get_all(header::ACCEPT)
+                .iter()
+                .collect::<Vec<_>>(),
+            ["text/html", "application/json"]
+        );
+        assert_eq!(builder.headers[header::USER_AGENT], "chzzk-rs");
+    }
+
+    #[tokio::test]
+    async fn test_headers() {
+        struct Referer;
+
+        impl Middleware for Referer {
+            fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+                request.headers_mut().insert(
+                    header::REFERER,
+                    HeaderValue::from_static("https://chzzk.naver.com/live"),
+                );
+
+                None
+            }
+        }
+
+        let server = HttpServer::start(|_| (StatusCode::NOT_FOUND, NOT_FOUND_CHANNEL));
+
+        let client = Client::builder()
+            .base_url(server.base_url())
+            .no_proxy()
+            .browser_headers()
+            .header(
+                HeaderName::from_static("x-custom"),
+                HeaderValue::from_static("1"),
+            )
+            .middleware(Referer)
+            .build()
+            .unwrap();
+
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+        assert!(endpoint.encode_ref().unwrap().headers().is_none());
+
+        let err = client.execute(endpoint).await.unwrap_err();
+        assert!(matches!(err, get_live_status::Error::NotFoundChannel(_)));
+
+        let head = server.requests()[0].to_lowercase();
+
+        assert!(head.contains(&format!(
+            "user-agent: {}",
+            BROWSER_USER_AGENT.to_lowercase()
+        )));
+        assert!(head.contains("origin: https://chzzk.naver.com\r\n"));
+        assert!(head.contains("referer: https://chzzk.naver.com/live\r\n"));
+        assert!(!head.contains("referer: https://chzzk.naver.com\r\n"));
+        assert!(head.contains("x-custom: 1\r\n"));
+    }
+}
@@ -0,0 +1,466 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use http::{header, HeaderMap, StatusCode};
+
+use crate::{
+    auth::{parse_set_cookie, Auth, AuthRotated, Secret},
+    request::Request,
+};
+
+/// Naver account used by a [`Client`](crate::Client).
+///
+/// the auth is updated with the session cookies rotated by Naver, and the account is
+/// marked as rejected once a response requires authentication. providers skip rejected accounts.
+pub struct Account {
+    /// random, identifies the responses of the account in the cache
+    id: u64,
+    auth: RwLock<Auth>,
+    rejected: AtomicBool,
+}
+
+impl Account {
+    pub fn new(auth: Auth) -> Arc<Self> {
+        Arc::new(Self {
+            id: fastrand::u64(..),
+            auth: RwLock::new(auth),
+            rejected: AtomicBool::new(false),
+        })
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// the auth with the rotated cookies applied
+    pub fn auth(&self) -> Auth {
+        self.auth.read().unwrap().clone()
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// makes the account available again, e.g. after its cookies have been replaced.
+    pub fn set_auth(&self, auth: Auth) {
+        *self.auth.write().unwrap() = auth;
+        self.rejected.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reject(&self) {
+        if !self.rejected.swap(true, Ordering::Relaxed) {
+            tracing::warn!("session rejected, excluding the account");
+        }
+    }
+
+    /// applies the session cookies set by `headers`. cookies being removed are ignored.
+    pub(crate) fn apply(&self, headers: &HeaderMap) -> Option<AuthRotated> {
+        let set_cookies = headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| parse_set_cookie(value.to_str().ok()?))
+            .collect::<Vec<_>>();
+
+        if set_cookies.is_empty() {
+            return None;
+        }
+
+        let mut auth = self.auth.write().unwrap();
+        let mut cookies = Vec::new();
+
+        for (name, value) in set_cookies {
+            let secret = match name {
+                "NID_SES" => &mut auth.nid_ses,
+                "NID_AUT" => &mut auth.nid_aut,
+                _ => &mut auth.nid_jkl,
+            };
+
+            if secret.expose() != value {
+                *secret = Secret::new(value);
+                cookies.push(name);
+            }
+        }
+
+        if cookies.is_empty() {
+            return None;
+        }
+
+        tracing::debug!(?cookies, "auth rotated");
+
+        Some(AuthRotated {
+            auth: auth.clone(),
+            cookies,
+        })
+    }
+
+    /// rejects the account if a response of `status` and `envelope` requires authentication
+    pub(crate) fn check(&self, status: StatusCode, envelope: Option<&crate::Error>) {
+        let rejected = status == StatusCode::UNAUTHORIZED
+            || envelope.is_some_and(|error| error.code == crate::Error::AUTH_REQUIRED);
+
+        if rejected {
+            self.reject();
+        }
+    }
+}
+
+/// Every account of a [`CredentialProvider`] is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("every account is rejected")]
+pub struct AccountsRejected;
+
+impl From<AccountsRejected> for crate::Error {
+    fn from(error: AccountsRejected) -> Self {
+        Self {
+            code: Self::AUTH_REQUIRED,
+            message: Some(error.to_string()),
+        }
+    }
+}
+
+/// Chooses the account of every request sent by a [`Client`](crate::Client).
+///
+/// set with [`ClientBuilder::credential_provider`](crate::client::ClientBuilder::credential_provider).
+/// rejected accounts should not be returned.
+pub trait CredentialProvider: Send + Sync + 'static {
+    /// `Ok(None)` sends `request` without credentials. `Err` fails it with the endpoint's
+    /// `AuthRequired` error instead of sending it.
+    fn credential(&self, request: &Request) -> Result<Option<Arc<Account>>, AccountsRejected>;
+}
+
+/// Uses the same account for every request.
+///
+/// requests fail once the account is rejected, until [`Account::set_auth`] is called.
+pub struct StaticCredentials {
+    account: Arc<Account>,
+}
+
+impl StaticCredentials {
+    pub fn new(account: Arc<Account>) -> Self {
+        Self { account }
+    }
+
+    pub fn account(&self) -> &Arc<Account> {
+        &self.account
+    }
+}
+
+impl CredentialProvider for StaticCredentials {
+    fn credential(&self, _request: &Request) -> Result<Option<Arc<Account>>, AccountsRejected> {
+        first_available([&self.account])
+    }
+}
+
+/// Uses an account per channel, and a fallback account for the other requests.
+///
+/// the channel is read from `/channels/:channel_id` of the request path.
+#[derive(Default)]
+pub struct ChannelCredentials {
+    channels: HashMap<String, Arc<Account>>,
+    fallback: Option<Arc<Account>>,
+}
+
+impl ChannelCredentials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn channel(mut self, channel_id: impl Into<String>, account: Arc<Account>) -> Self {
+        self.channels.insert(channel_id.into(), account);
+        self
+    }
+
+    /// used for channels without an account, and if their account is rejected
+    pub fn fallback(mut self, account: impl Into<Option<Arc<Account>>>) -> Self {
+        self.fallback = account.into();
+        self
+    }
+}
+
+impl CredentialProvider for ChannelCredentials {
+    /// `Ok(None)` if neither the channel nor the fallback has an account.
+    fn credential(&self, request: &Request) -> Result<Option<Arc<Account>>, AccountsRejected> {
+        first_available(
+            channel_id(request.path())
+                .and_then(|channel_id| self.channels.get(channel_id))
+                .into_iter()
+                .chain(&self.fallback),
+        )
+    }
+}
+
+/// Rotates through the accounts request by request, skipping rejected ones.
+pub struct RoundRobinCredentials {
+    accounts: Vec<Arc<Account>>,
+    next: AtomicUsize,
+}
+
+impl RoundRobinCredentials {
+    pub fn new(accounts: impl IntoIterator<Item = Arc<Account>>) -> Self {
+        Self {
+            accounts: accounts.into_iter().collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn accounts(&self) -> &[Arc<Account>] {
+        &self.accounts
+    }
+}
+
+impl CredentialProvider for RoundRobinCredentials {
+    fn credential(&self, _request: &Request) -> Result<Option<Arc<Account>>, AccountsRejected> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        first_available(
+            (0..self.accounts.len()).map(|i| &self.accounts[(start + i) % self.accounts.len()]),
+        )
+    }
+}
+
+/// the first account of `accounts` which is not rejected, `None` if there are no accounts.
+fn first_available<'a>(
+    accounts: impl IntoIterator<Item = &'a Arc<Account>>,
+) -> Result<Option<Arc<Account>>, AccountsRejected> {
+    let mut accounts = accounts.into_iter().peekable();
+
+    if accounts.peek().is_none() {
+        return Ok(None);
+    }
+
+    accounts
+        .find(|x| !x.is_rejected())
+        .cloned()
+        .map(Some)
+        .ok_or(AccountsRejected)
+}
+
+/// `:channel_id` of `/channels/:channel_id` in `path`
+fn channel_id(path: &str) -> Option<&str> {
+    let mut segments = path.split('/');
+
+    segments.find(|x| *x == "channels")?;
+    segments.next().filter(|x| !x.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use bytes::Bytes;
+    use http::HeaderValue;
+
+    use super::*;
+    use crate::{
+        cache::{Cache, CachePolicy},
+        fixtures::{Respond, LIVE_STATUS},
+        live::{get_live_status, get_live_status::GetLiveStatus},
+        middleware::Middleware,
+        request::Encode,
+        Client,
+    };
+
+    fn request(channel_id: &str) -> Request {
+        GetLiveStatus { channel_id }.encode().unwrap()
+    }
+
+    fn credential(provider: &impl CredentialProvider, channel_id: &str) -> Option<Arc<Account>> {
+        provider.credential(&request(channel_id)).unwrap()
+    }
+
+    #[test]
+    fn test_providers() {
+        let a = Account::new(Auth::new("a", "a", "a"));
+        let b = Account::new(Auth::new("b", "b", "b"));
+
+        let round_robin = RoundRobinCredentials::new([a.clone(), b.clone()]);
+        let chosen = (0..4)
+            .map(|_| credential(&round_robin, "x").unwrap().auth())
+            .collect::<Vec<_>>();
+        assert_eq!(chosen, [a.auth(), b.auth(), a.auth(), b.auth()]);
+
+        let channels = ChannelCredentials::new()
+            .channel("x", a.clone())
+            .fallback(b.clone());
+        assert!(Arc::ptr_eq(&credential(&channels, "x").unwrap(), &a));
+        assert!(Arc::ptr_eq(&credential(&channels, "y").unwrap(), &b));
+
+        a.reject();
+
+        assert!(Arc::ptr_eq(&credential(&channels, "x").unwrap(), &b));
+        assert!((0..4).all(|_| Arc::ptr_eq(&credential(&round_robin, "x").unwrap(), &b)));
+        assert_eq!(
+            StaticCredentials::new(a.clone())
+                .credential(&request("x"))
+                .err(),
+            Some(AccountsRejected)
+        );
+
+        b.reject();
+
+        assert_eq!(
+            round_robin.credential(&request("x")).err(),
+            Some(AccountsRejected)
+        );
+        assert_eq!(
+            channels.credential(&request("x")).err(),
+            Some(AccountsRejected)
+        );
+        assert!(matches!(
+            ChannelCredentials::new().credential(&request("x")),
+            Ok(None)
+        ));
+
+        a.set_auth(Auth::new("c", "c", "c"));
+
+        assert!(!a.is_rejected());
+    }
+
+    /// rotates `NID_SES` of the first account, and rejects the second one.
+    struct Server;
+
+    impl Middleware for Server {
+        fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+            let cookie = request.headers().and_then(|x| x.get(header::COOKIE));
+            let cookie = cookie.and_then(|x| x.to_str().ok()).unwrap_or_default();
+
+            let mut response = http::Response::new(Bytes::from_static(LIVE_STATUS.as_bytes()));
+
+            if cookie.contains("NID_SES=a") {
+                response.headers_mut().insert(
+                    header::SET_COOKIE,
+                    HeaderValue::from_static("NID_SES=rotated; Path=/; Domain=.naver.com"),
+                );
+            } else if cookie.contains("NID_SES=b") {
+                *response.status_mut() = StatusCode::UNAUTHORIZED;
+                *response.body_mut() =
+                    Bytes::from_static(br#"{"code":401,"message":"Unauthorized","content":null}"#);
+            }
+
+            Some(response)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotation_and_rejection() {
+        let a = Account::new(Auth::new("a", "a", "a"));
+        let b = Account::new(Auth::new("b", "b", "b"));
+        let rotated = Arc::new(Mutex::new(Vec::new()));
+
+        let client = Client::builder()
+            .credential_provider(
+                ChannelCredentials::new()
+                    .channel("x", a.clone())
+                    .channel("y", b.clone()),
+            )
+            .middleware(Server)
+            .on_auth_rotated({
+                let rotated = rotated.clone();
+
+                move |event| rotated.lock().unwrap().push(event.cookies.clone())
+            })
+            .build()
+            .unwrap();
+
+        client
+            .execute(GetLiveStatus { channel_id: "x" })
+            .await
+            .unwrap();
+
+        assert_eq!(a.auth(), Auth::new("rotated", "a", "a"));
+        assert_eq!(*rotated.lock().unwrap(), [["NID_SES"]]);
+
+        client
+            .execute(GetLiveStatus { channel_id: "y" })
+            .await
+            .unwrap_err();
+
+        assert!(b.is_rejected());
+        assert!(!a.is_rejected());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_auth() {
+        let cookies = Arc::new(Cookies::default());
+
+        let client = Client::builder()
+            .auth(Auth::new("b", "b", "b"))
+            .middleware(cookies.clone())
+            .middleware(Server)
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            let err = client
+                .execute(GetLiveStatus { channel_id: "x" })
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, get_live_status::Error::AuthRequired(_)));
+        }
+
+        // the second request is not sent without the rejected cookies
+        assert_eq!(cookies.0.lock().unwrap().len(), 1);
+
+        let account = client.account().unwrap();
+        assert!(account.is_rejected());
+
+        account.set_auth(Auth::new("a", "a", "a"));
+
+        client
+            .execute(GetLiveStatus { channel_id: "x" })
+            .await
+            .unwrap();
+    }
+
+    /// records the `Cookie` header of every request sent
+    #[derive(Default)]
+    struct Cookies(Mutex<Vec<String>>);
+
+    impl Middleware for Arc<Cookies> {
+        fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+            let cookie = request.headers().and_then(|x| x.get(header::COOKIE));
+            let cookie = cookie.and_then(|x| x.to_str().ok()).unwrap_or_default();
+
+            self.0.lock().unwrap().push(cookie.to_owned());
+
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_per_account() {
+        let a = Account::new(Auth::new("a", "a", "a"));
+        let b = Account::new(Auth::new("b", "b", "b"));
+        let cookies = Arc::new(Cookies::default());
+
+        let client = Client::builder()
+            .credential_provider(RoundRobinCredentials::new([a, b]))
+            .cache(Cache::new(CachePolicy {
+                default_ttl: Some(std::time::Duration::from_secs(60)),
+                ..Default::default()
+            }))
+            .middleware(cookies.clone())
+            .middleware(Respond::ok(LIVE_STATUS))
+            .build()
+            .unwrap();
+
+        for _ in 0..4 {
+            client
+                .execute(GetLiveStatus { channel_id: "x" })
+                .await
+                .unwrap();
+        }
+
+        // the response cached for `a` is not served to `b`
+        let cookies = cookies.0.lock().unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert!(cookies[0].contains("NID_SES=a"));
+        assert!(cookies[1].contains("NID_SES=b"));
+    }
+}
@@ -1,13 +1,50 @@
+use std::fmt::Display;
+
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+/// Error envelope returned by Chzzk, `{ "code": .., "message": .. }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct Error {
     pub code: i64,
-    pub message: String,
+    pub message: Option<String>,
+}
+
+impl Error {
+    pub const OK: i64 = 200;
+    pub const AUTH_REQUIRED: i64 = 401;
+    pub const ADULT_RESTRICTED: i64 = 403;
+    pub const NOT_FOUND_CHANNEL: i64 = 404;
+    pub const RATE_LIMITED: i64 = 429;
+
+    pub(crate) fn from_slice(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{} {}", self.code, message),
+            None => write!(f, "{}", self.code),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Generates the error type of an endpoint.
+///
+/// every endpoint error can be decoded from the Chzzk error envelope. known codes are mapped to
+/// `AuthRequired` and `RateLimited`, and endpoint-specific codes to the listed variants:
+///
+/// ```ignore
+/// expand_error![
+///     #[error("not found channel: {0}")]
+///     NotFoundChannel = crate::Error::NOT_FOUND_CHANNEL,
+/// ];
+/// ```
 macro_rules! expand_error {
-    ($($(#[$attr:meta])+ $error:tt $(,)?)*) => {
+    ($($(#[$attr:meta])+ $error:ident = $code:path $(,)?)*) => {
         #[derive(Debug, thiserror::Error)]
         pub enum Error {
             #[error("encode: {0}")]
@@ -15,23 +52,79 @@ macro_rules! expand_error {
             #[error("decode: {0}")]
             Decode(#[from] $crate::request::DecodeError),
             #[error("reqwest: {0}")]
-            Reqwest(#[from] ::reqwest::Error),
+            Reqwest(::reqwest::Error),
+            #[error("timeout")]
+            Timeout,
 
             #[error("{0}: {1}")]
             Undefined(::http::StatusCode, String),
 
+            #[error("{0}: chzzk: {1}")]
+            Chzzk(::http::StatusCode, $crate::Error),
+            #[error("auth required: {0}")]
+            AuthRequired($crate::Error),
+            #[error("rate limited: {0}")]
+            RateLimited($crate::Error),
+
             $(
                 $(
                     #[$attr]
                 )+
-                $error,
+                $error($crate::Error),
             )*
         }
 
+        impl From<::reqwest::Error> for Error {
+            fn from(error: ::reqwest::Error) -> Self {
+                if error.is_timeout() {
+                    Self::Timeout
+                } else {
+                    Self::Reqwest(error)
+                }
+            }
+        }
+
         impl Error {
-            pub(crate) async fn from_response(resp: ::reqwest::Response) -> Self {
-                match resp.status() {
-                    code => Self::Undefined(code, resp.text().await.unwrap_or_default())
+            /// whether the request may succeed if sent again: connection failures, timeouts,
+            /// `5xx`, `408` and `429` responses or envelope codes, and rate limited envelopes.
+            pub fn is_retryable(&self) -> bool {
+                match self {
+                    Self::Reqwest(error) => $crate::retry::is_retryable_reqwest(error),
+                    Self::Undefined(status, _) => $crate::retry::is_retryable_status(*status),
+                    Self::Chzzk(status, error) => {
+                        $crate::retry::is_retryable_status(*status)
+                            || $crate::retry::is_retryable_code(error.code)
+                    }
+                    Self::Timeout | Self::RateLimited(_) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl $crate::request::EndpointError for Error {
+            fn is_retryable(&self) -> bool {
+                Error::is_retryable(self)
+            }
+
+            fn from_parsed_response(
+                status: ::http::StatusCode,
+                body: ::bytes::Bytes,
+                envelope: Option<$crate::Error>,
+            ) -> Self {
+                match envelope {
+                    Some(error) => Self::from_envelope(status, error),
+                    None => Self::Undefined(status, String::from_utf8_lossy(&body).into_owned()),
+                }
+            }
+
+            fn from_envelope(status: ::http::StatusCode, error: $crate::Error) -> Self {
+                match error.code {
+                    $crate::Error::AUTH_REQUIRED => Self::AuthRequired(error),
+                    $crate::Error::RATE_LIMITED => Self::RateLimited(error),
+                    $(
+                        $code => Self::$error(error),
+                    )*
+                    _ => Self::Chzzk(status, error),
                 }
             }
         }
@@ -0,0 +1,159 @@
+//! Responses of Chzzk used by tests.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+use crate::{middleware::Middleware, request::Request};
+
+/// cassette replaying [`LIVE_STATUS`] and [`LIVE_DETAIL`] of `475313e6c26639d5763628313b4c130e`
+pub(crate) const LIVE_CASSETTE: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/live.json");
+
+pub(crate) const LIVE_STATUS: &str = r#"{"code":200,"message":null,"content":{"liveTitle":"title","status":"OPEN","concurrentUserCount":10,"accumulateCount":100,"paidPromotion":false,"adult":false,"chatChannelId":"N1a2b3","categoryType":"GAME","liveCategory":"talk","liveCategoryValue":"talk","livePollingStatusJson":"{\"status\":\"STARTED\",\"isPublishing\":true,\"playableStatus\":\"PLAYABLE\",\"trafficThrottling\":-1,\"callPeriodMilliSecond\":10000}","userAdultStatus":null,"chatActive":true,"chatAvailableGroup":"ALL","chatAvailableCondition":"NONE","minFollowerMinute":0}}"#;
+
+pub(crate) const LIVE_DETAIL: &str = r#"{"code":200,"message":null,"content":{"liveId":1234567,"liveTitle":"title","status":"OPEN","liveImageUrl":"https://livecloud-thumb.akamaized.net/chzzk/livecloud/image_{type}.jpg","defaultThumbnailImageUrl":null,"concurrentUserCount":10,"accumulateCount":100,"openDate":"2024-03-01 12:00:00","closeDate":null,"adult":false,"chatChannelId":"N1a2b3","categoryType":"GAME","liveCategory":"talk","liveCategoryValue":"talk","chatActive":true,"chatAvailableGroup":"ALL","paidPromotion":false,"chatAvailableCondition":"NONE","minFollowerMinute":0,"livePlaybackJson":null,"channel":{"channelId":"475313e6c26639d5763628313b4c130e","channelName":"channel","channelImageUrl":null,"verifiedMark":false},"livePollingStatusJson":"{\"status\":\"STARTED\",\"isPublishing\":true,\"playableStatus\":\"PLAYABLE\",\"trafficThrottling\":-1,\"callPeriodMilliSecond\":10000}","userAdultStatus":null}}"#;
+
+pub(crate) const NOT_FOUND_CHANNEL: &str =
+    r#"{"code":404,"message":"Not Found Channel","content":null}"#;
+
+/// Middleware responding a canned response to every request instead of sending it.
+pub(crate) struct Respond {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: &'static str,
+    /// requests whose path contains it are responded [`NOT_FOUND_CHANNEL`]
+    not_found: Option<&'static str>,
+}
+
+impl Respond {
+    pub(crate) fn new(status: StatusCode, body: &'static str) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body,
+            not_found: None,
+        }
+    }
+
+    pub(crate) fn ok(body: &'static str) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+
+    pub(crate) fn header(mut self, name: HeaderName, value: &'static str) -> Self {
+        self.headers.insert(name, HeaderValue::from_static(value));
+        self
+    }
+
+    /// responds `404` [`NOT_FOUND_CHANNEL`] to requests whose path contains `pattern`
+    pub(crate) fn not_found(mut self, pattern: &'static str) -> Self {
+        self.not_found = Some(pattern);
+        self
+    }
+}
+
+impl Middleware for Respond {
+    fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+        let (status, body) = match self.not_found {
+            Some(pattern) if request.path().contains(pattern) => {
+                (StatusCode::NOT_FOUND, NOT_FOUND_CHANNEL)
+            }
+            _ => (self.status, self.body),
+        };
+
+        let mut response = http::Response::new(Bytes::from_static(body.as_bytes()));
+
+        *response.status_mut() = status;
+        *response.headers_mut() = self.headers.clone();
+
+        Some(response)
+    }
+}
+
+/// HTTP/1.1 server on `127.0.0.1` responding `respond(head)` to every request, where `head` is
+/// the request line and headers. connections are kept alive.
+pub(crate) struct HttpServer {
+    base_url: String,
+    connections: Arc<AtomicUsize>,
+    heads: Arc<Mutex<Vec<String>>>,
+}
+
+impl HttpServer {
+    pub(crate) fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&str) -> (StatusCode, &'static str) + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let heads = Arc::new(Mutex::new(Vec::new()));
+        let respond = Arc::new(respond);
+
+        thread::spawn({
+            let connections = connections.clone();
+            let heads = heads.clone();
+
+            move || {
+                for stream in listener.incoming() {
+                    connections.fetch_add(1, Ordering::Relaxed);
+
+                    let heads = heads.clone();
+                    let respond = respond.clone();
+
+                    thread::spawn(move || {
+                        let mut reader = BufReader::new(stream.unwrap());
+                        let mut head = String::new();
+
+                        while reader.read_line(&mut head).unwrap_or(0) > 0 {
+                            if !head.ends_with("\r\n\r\n") {
+                                continue;
+                            }
+
+                            heads.lock().unwrap().push(head.clone());
+
+                            let (status, body) = respond(&head);
+
+                            write!(
+                                reader.get_mut(),
+                                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                                body.len()
+                            )
+                            .unwrap();
+
+                            head.clear();
+                        }
+                    });
+                }
+            }
+        });
+
+        Self {
+            base_url,
+            connections,
+            heads,
+        }
+    }
+
+    pub(crate) fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    /// number of connections accepted so far
+    pub(crate) fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// heads of the requests received so far
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.heads.lock().unwrap().clone()
+    }
+}
@@ -1,8 +1,31 @@
+pub mod auth;
+mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod cassette;
+pub mod client;
+pub mod credential;
 mod error;
+#[cfg(test)]
+mod fixtures;
 pub mod live;
+pub mod middleware;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod model;
+pub mod rate_limit;
 pub mod request;
+pub mod response;
+pub mod retry;
+#[cfg(feature = "tower")]
+mod service;
+mod single_flight;
+mod telemetry;
+pub mod user;
 
+pub use client::Client;
 pub use error::Error;
 
 pub const CHZZK_API_URL: &str = "https://api.chzzk.naver.com";
+pub const GAME_API_URL: &str = "https://comm-api.game.naver.com";
@@ -1,15 +1,22 @@
 use bytes::Bytes;
-use http::{Method, StatusCode};
+use http::Method;
 use serde::Serialize;
 
 use crate::{
     error::expand_error,
     model,
-    request::{Auth, ChzzkResponse, Decode, DecodeError, Encode, EncodeError, Request},
+    request::{
+        decode_content, Api, Auth, Decode, DecodeError, Encode, EncodeError, Endpoint, Request,
+    },
     CHZZK_API_URL,
 };
 
-expand_error![];
+expand_error![
+    #[error("not found channel: {0}")]
+    NotFoundChannel = crate::Error::NOT_FOUND_CHANNEL,
+    #[error("adult restricted: {0}")]
+    AdultRestricted = crate::Error::ADULT_RESTRICTED,
+];
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Clone, PartialEq)]
@@ -19,19 +26,28 @@ pub struct GetLiveDetail<'a> {
 
 impl<'a> GetLiveDetail<'a> {
     pub async fn send(&self, token: impl Into<Option<&Auth>>) -> Result<model::LiveDetail, Error> {
-        let resp = self.encode_ref()?.send(token).await?;
-
-        match resp.status() {
-            StatusCode::OK => {
-                let r = GetLiveDetail::decode(resp.bytes().await?)?;
+        self.encode_ref()?
+            .execute::<Self>(CHZZK_API_URL, token.into())
+            .await
+    }
 
-                Ok(r)
-            }
-            _ => Err(Error::from_response(resp).await),
-        }
+    #[cfg(feature = "blocking")]
+    pub fn send_blocking<'t>(
+        &self,
+        token: impl Into<Option<&'t Auth>>,
+    ) -> Result<model::LiveDetail, Error> {
+        crate::blocking::block_on(self.send(token))
     }
 }
 
+impl<'a> Endpoint for GetLiveDetail<'a> {
+    type Error = Error;
+
+    const NAME: &'static str = "GetLiveDetail";
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/service/v2/channels/:streamer_id/live-detail";
+}
+
 #[derive(Serialize)]
 struct Path<'a> {
     streamer_id: &'a str,
@@ -43,18 +59,17 @@ impl<'a> Encode for GetLiveDetail<'a> {
             channel_id: streamer_id,
         } = *self;
 
-        let path = serde_path::to_string(
-            "/service/v2/channels/:streamer_id/live-detail",
-            &Path { streamer_id },
-        )?;
+        let path = serde_path::to_string(Self::PATH, &Path { streamer_id })?;
 
         Ok(Request {
-            base_url: CHZZK_API_URL,
-            method: Method::GET,
+            api: Api::Chzzk,
+            base_url: None,
+            method: Self::METHOD,
             path: path.into(),
             headers: None,
             body: None,
             query: None,
+            timeout: None,
         })
     }
 }
@@ -65,26 +80,39 @@ impl<'a> Decode for GetLiveDetail<'a> {
     fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError> {
         // println!("{}", String::from_utf8(bytes.to_vec()).unwrap());
 
-        let deserialized: ChzzkResponse<model::sealed::LiveDetail> =
-            serde_json::from_slice(&bytes)?;
-
-        Ok(deserialized.content.try_into()?)
+        decode_content::<model::sealed::LiveDetail, _>(&bytes)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{cassette::Cassette, fixtures::LIVE_CASSETTE, model::LiveStatusType, Client};
 
     #[tokio::test]
     async fn test_detail() {
-        let live_detail = GetLiveDetail {
-            channel_id: "475313e6c26639d5763628313b4c130e",
-        }
-        .send(None)
-        .await
-        .unwrap();
-
-        println!("{:#?}", live_detail);
+        let client = Client::builder()
+            .cassette(Cassette::replay(LIVE_CASSETTE).unwrap())
+            .build()
+            .unwrap();
+
+        let live_detail = client
+            .execute(GetLiveDetail {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_detail.inherit.live_id, 1234567);
+        assert_eq!(live_detail.inherit.live_title, "title");
+        assert_eq!(live_detail.inherit.open_date, "2024-03-01 12:00:00");
+        assert_eq!(live_detail.inherit.live_playback, None);
+        assert_eq!(
+            live_detail.inherit.channel.channel_id,
+            "475313e6c26639d5763628313b4c130e"
+        );
+        assert_eq!(live_detail.status, LiveStatusType::Open);
+        assert_eq!(live_detail.close_date, None);
+        assert_eq!(live_detail.live_polling_status.call_period_ms, 10000);
     }
 }
@@ -1,15 +1,21 @@
 use bytes::Bytes;
-use http::{Method, StatusCode};
+use http::Method;
 use serde::Serialize;
 
 use crate::{
     error::expand_error,
     model,
-    request::{Auth, ChzzkResponse, Decode, DecodeError, Encode, EncodeError, Request},
+    rate_limit::RateLimitHint,
+    request::{
+        decode_content, Api, Auth, Decode, DecodeError, Encode, EncodeError, Endpoint, Request,
+    },
     CHZZK_API_URL,
 };
 
-expand_error![];
+expand_error![
+    #[error("not found channel: {0}")]
+    NotFoundChannel = crate::Error::NOT_FOUND_CHANNEL,
+];
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Clone, PartialEq)]
@@ -19,16 +25,29 @@ pub struct GetLiveStatus<'a> {
 
 impl<'a> GetLiveStatus<'a> {
     pub async fn send(&self, token: impl Into<Option<&Auth>>) -> Result<model::LiveStatus, Error> {
-        let resp = self.encode_ref()?.send(token).await?;
+        self.encode_ref()?
+            .execute::<Self>(CHZZK_API_URL, token.into())
+            .await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn send_blocking<'t>(
+        &self,
+        token: impl Into<Option<&'t Auth>>,
+    ) -> Result<model::LiveStatus, Error> {
+        crate::blocking::block_on(self.send(token))
+    }
+}
+
+impl<'a> Endpoint for GetLiveStatus<'a> {
+    type Error = Error;
 
-        match resp.status() {
-            StatusCode::OK => {
-                let r = GetLiveStatus::decode(resp.bytes().await?)?;
+    const NAME: &'static str = "GetLiveStatus";
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/polling/v2/channels/:channel_id/live-status";
 
-                Ok(r)
-            }
-            _ => Err(Error::from_response(resp).await),
-        }
+    fn rate_limit_hint(output: &Self::Output) -> Option<RateLimitHint> {
+        Some((&output.live_polling_status).into())
     }
 }
 
@@ -41,18 +60,17 @@ impl<'a> Encode for GetLiveStatus<'a> {
     fn encode_ref(&self) -> Result<Request, EncodeError> {
         let GetLiveStatus { channel_id } = *self;
 
-        let path = serde_path::to_string(
-            "/polling/v2/channels/:channel_id/live-status",
-            &Path { channel_id },
-        )?;
+        let path = serde_path::to_string(Self::PATH, &Path { channel_id })?;
 
         Ok(Request {
-            base_url: CHZZK_API_URL,
-            method: Method::GET,
+            api: Api::Chzzk,
+            base_url: None,
+            method: Self::METHOD,
             path: path.into(),
             headers: None,
             body: None,
             query: None,
+            timeout: None,
         })
     }
 }
@@ -63,26 +81,43 @@ impl<'a> Decode for GetLiveStatus<'a> {
     fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError> {
         // println!("{}", String::from_utf8(bytes.to_vec()).unwrap());
 
-        let deserialized: ChzzkResponse<model::sealed::LiveStatus> =
-            serde_json::from_slice(&bytes)?;
-
-        Ok(deserialized.content.try_into()?)
+        decode_content::<model::sealed::LiveStatus, _>(&bytes)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        cassette::Cassette,
+        fixtures::LIVE_CASSETTE,
+        model::{LivePollingStatusType, LiveStatusType},
+        Client,
+    };
 
     #[tokio::test]
     async fn test_status() {
-        let live_status = GetLiveStatus {
-            channel_id: "475313e6c26639d5763628313b4c130e",
-        }
-        .send(None)
-        .await
-        .unwrap();
-
-        println!("{:#?}", live_status);
+        let client = Client::builder()
+            .cassette(Cassette::replay(LIVE_CASSETTE).unwrap())
+            .build()
+            .unwrap();
+
+        let live_status = client
+            .execute(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_status.live_title, "title");
+        assert_eq!(live_status.status, LiveStatusType::Open);
+        assert_eq!(live_status.concurrent_user_count, 10);
+        assert_eq!(live_status.chat_channel_id.as_deref(), Some("N1a2b3"));
+        assert_eq!(
+            live_status.live_polling_status.status,
+            LivePollingStatusType::Started
+        );
+        assert_eq!(live_status.live_polling_status.call_period_ms, 10000);
+        assert_eq!(live_status.user_adult_status, None);
     }
 }
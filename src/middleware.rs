@@ -0,0 +1,92 @@
+use bytes::Bytes;
+
+use crate::request::Request;
+
+/// Hooks around every request sent by [`Client`](crate::Client).
+///
+/// middlewares run in the order they were added for [`Middleware::before_send`],
+/// and in reverse order for [`Middleware::after_response`]. both hooks run on every attempt.
+pub trait Middleware: Send + Sync + 'static {
+    /// called before `request` is sent. returning a response skips sending it
+    /// and the remaining `before_send` hooks.
+    fn before_send(&self, _request: &mut Request) -> Option<http::Response<Bytes>> {
+        None
+    }
+
+    /// called with the response of `request` before it is decoded.
+    fn after_response(&self, _request: &Request, _response: &mut http::Response<Bytes>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use http::{header, HeaderValue};
+
+    use super::*;
+    use crate::{
+        fixtures::{Respond, LIVE_STATUS},
+        live::{get_live_status, get_live_status::GetLiveStatus},
+        Client,
+    };
+
+    struct UserAgent;
+
+    impl Middleware for UserAgent {
+        fn before_send(&self, request: &mut Request) -> Option<http::Response<Bytes>> {
+            request
+                .headers_mut()
+                .insert(header::USER_AGENT, HeaderValue::from_static("chzzk-rs"));
+
+            None
+        }
+    }
+
+    #[derive(Default)]
+    struct Count(AtomicUsize);
+
+    impl Middleware for Arc<Count> {
+        fn after_response(&self, request: &Request, _response: &mut http::Response<Bytes>) {
+            assert_eq!(
+                request.headers().and_then(|x| x.get(header::USER_AGENT)),
+                Some(&HeaderValue::from_static("chzzk-rs"))
+            );
+
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let count = Arc::new(Count::default());
+
+        let client = Client::builder()
+            .middleware(UserAgent)
+            .middleware(count.clone())
+            .middleware(Respond::ok(LIVE_STATUS).not_found("unknown"))
+            .build()
+            .unwrap();
+
+        let live_status = client
+            .execute(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(live_status.live_title, "title");
+
+        let err = client
+            .execute(GetLiveStatus {
+                channel_id: "unknown",
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, get_live_status::Error::NotFoundChannel(_)));
+        assert_eq!(count.0.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,502 @@
+//! In-process server emulating the Chzzk API, for testing without the network.
+//!
+//! ```ignore
+//! let server = MockServer::start().await?;
+//! let client = server.client_builder().build()?;
+//!
+//! let channel = server.channel("475313e6c26639d5763628313b4c130e");
+//! channel.go_live("title");
+//!
+//! let live_status = client.execute(GetLiveStatus { channel_id: channel.id() }).await?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+};
+
+use http::{header, Method, StatusCode};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body,
+};
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+use crate::{client::ClientBuilder, model::LiveStatusType, Client};
+
+/// Emulates `/polling/v2/channels/:channel_id/live-status` and
+/// `/service/v2/channels/:channel_id/live-detail`, and `/nng_main/v1/user/getUserStatus`
+/// of the game API which always responds a logged out user.
+///
+/// channels which were never referenced by [`MockServer::channel`] respond `404 Not Found Channel`.
+/// the server shuts down when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    _shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct State {
+    channels: HashMap<String, Channel>,
+    /// `METHOD /path` of every received request
+    requests: Vec<String>,
+    next_live_id: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Channel {
+    name: String,
+    title: String,
+    status: LiveStatusType,
+    live_id: u64,
+    concurrent_user_count: u64,
+    accumulate_count: u64,
+    category: Option<String>,
+    adult: bool,
+    open_date: String,
+    close_date: Option<String>,
+    call_period_ms: u64,
+    error: Option<(StatusCode, crate::Error)>,
+}
+
+impl MockServer {
+    /// binds to a random port of `127.0.0.1` and serves on the current tokio runtime.
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let (shutdown, rx) = oneshot::channel::<()>();
+
+        let make_service = {
+            let state = state.clone();
+
+            make_service_fn(move |_| {
+                let state = state.clone();
+
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req| {
+                        let response = handle(&state, req.method(), req.uri().path());
+
+                        async move { Ok::<_, Infallible>(response) }
+                    }))
+                }
+            })
+        };
+
+        let server = hyper::Server::from_tcp(listener)
+            .map_err(io::Error::other)?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                rx.await.ok();
+            });
+
+        tokio::spawn(async move {
+            if let Err(err) = server.await {
+                tracing::error!("mock server: {err}");
+            }
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            _shutdown: shutdown,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// client builder whose base urls are this server
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .base_url(self.base_url())
+            .game_base_url(self.base_url())
+            .no_proxy()
+    }
+
+    /// creates the channel if it doesn't exist, closed.
+    pub fn channel(&self, channel_id: impl Into<String>) -> MockChannel {
+        let id = channel_id.into();
+
+        self.state
+            .lock()
+            .unwrap()
+            .channels
+            .entry(id.clone())
+            .or_insert_with(|| Channel::new(&id));
+
+        MockChannel {
+            id,
+            state: self.state.clone(),
+        }
+    }
+
+    /// `METHOD /path` of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+/// Scenario handle of a channel of [`MockServer`].
+#[derive(Clone)]
+pub struct MockChannel {
+    id: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockChannel {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Channel, &mut u64)) -> &Self {
+        let mut state = self.state.lock().unwrap();
+        let State {
+            channels,
+            next_live_id,
+            ..
+        } = &mut *state;
+
+        if let Some(channel) = channels.get_mut(&self.id) {
+            f(channel, next_live_id);
+        }
+
+        self
+    }
+
+    /// starts a new live
+    pub fn go_live(&self, title: impl Into<String>) -> &Self {
+        let title = title.into();
+
+        self.update(|channel, next_live_id| {
+            *next_live_id += 1;
+
+            channel.title = title;
+            channel.status = LiveStatusType::Open;
+            channel.live_id = *next_live_id;
+            channel.open_date = now();
+            channel.close_date = None;
+        })
+    }
+
+    pub fn set_title(&self, title: impl Into<String>) -> &Self {
+        let title = title.into();
+
+        self.update(|channel, _| channel.title = title)
+    }
+
+    pub fn set_category(&self, category: impl Into<Option<String>>) -> &Self {
+        let category = category.into();
+
+        self.update(|channel, _| channel.category = category)
+    }
+
+    pub fn set_adult(&self, adult: bool) -> &Self {
+        self.update(|channel, _| channel.adult = adult)
+    }
+
+    pub fn set_viewers(&self, concurrent_user_count: u64) -> &Self {
+        self.update(|channel, _| {
+            channel.concurrent_user_count = concurrent_user_count;
+            channel.accumulate_count += concurrent_user_count;
+        })
+    }
+
+    /// `callPeriodMilliSecond` of the live polling status
+    pub fn set_call_period_ms(&self, call_period_ms: u64) -> &Self {
+        self.update(|channel, _| channel.call_period_ms = call_period_ms)
+    }
+
+    /// ends the live
+    pub fn end(&self) -> &Self {
+        self.update(|channel, _| {
+            channel.status = LiveStatusType::Close;
+            channel.concurrent_user_count = 0;
+            channel.close_date = Some(now());
+        })
+    }
+
+    /// responds every request of the channel with the error envelope until [`MockChannel::recover`].
+    pub fn fail(&self, status: StatusCode, code: i64, message: impl Into<Option<String>>) -> &Self {
+        let error = crate::Error {
+            code,
+            message: message.into(),
+        };
+
+        self.update(|channel, _| channel.error = Some((status, error)))
+    }
+
+    pub fn recover(&self) -> &Self {
+        self.update(|channel, _| channel.error = None)
+    }
+
+    /// the channel responds `404 Not Found Channel` afterwards
+    pub fn remove(&self) {
+        self.state.lock().unwrap().channels.remove(&self.id);
+    }
+}
+
+impl Channel {
+    fn new(id: &str) -> Self {
+        Self {
+            name: format!("channel {id}"),
+            title: String::new(),
+            status: LiveStatusType::Close,
+            live_id: 0,
+            concurrent_user_count: 0,
+            accumulate_count: 0,
+            category: None,
+            adult: false,
+            open_date: now(),
+            close_date: None,
+            call_period_ms: 10000,
+            error: None,
+        }
+    }
+
+    fn live_polling_status_json(&self) -> String {
+        let (status, is_publishing) = match self.status {
+            LiveStatusType::Open => ("STARTED", true),
+            LiveStatusType::Close => ("ENDED", false),
+        };
+
+        json!({
+            "status": status,
+            "isPublishing": is_publishing,
+            "playableStatus": "PLAYABLE",
+            "trafficThrottling": -1,
+            "callPeriodMilliSecond": self.call_period_ms,
+        })
+        .to_string()
+    }
+
+    fn live_status(&self) -> Value {
+        json!({
+            "liveTitle": self.title,
+            "status": self.status,
+            "concurrentUserCount": self.concurrent_user_count,
+            "accumulateCount": self.accumulate_count,
+            "paidPromotion": false,
+            "adult": self.adult,
+            "chatChannelId": self.chat_channel_id(),
+            "categoryType": self.category.as_ref().map(|_| "GAME"),
+            "liveCategory": self.category,
+            "liveCategoryValue": self.category,
+            "livePollingStatusJson": self.live_polling_status_json(),
+            "userAdultStatus": null,
+            "chatActive": true,
+            "chatAvailableGroup": "ALL",
+            "chatAvailableCondition": "NONE",
+            "minFollowerMinute": 0,
+        })
+    }
+
+    fn live_detail(&self, id: &str) -> Value {
+        json!({
+            "liveId": self.live_id,
+            "liveTitle": self.title,
+            "status": self.status,
+            "liveImageUrl": null,
+            "defaultThumbnailImageUrl": null,
+            "concurrentUserCount": self.concurrent_user_count,
+            "accumulateCount": self.accumulate_count,
+            "openDate": self.open_date,
+            "closeDate": self.close_date,
+            "adult": self.adult,
+            "chatChannelId": self.chat_channel_id(),
+            "categoryType": self.category.as_ref().map(|_| "GAME"),
+            "liveCategory": self.category,
+            "liveCategoryValue": self.category,
+            "chatActive": true,
+            "chatAvailableGroup": "ALL",
+            "paidPromotion": false,
+            "chatAvailableCondition": "NONE",
+            "minFollowerMinute": 0,
+            "livePlaybackJson": null,
+            "channel": {
+                "channelId": id,
+                "channelName": self.name,
+                "channelImageUrl": null,
+                "verifiedMark": false,
+            },
+            "livePollingStatusJson": self.live_polling_status_json(),
+            "userAdultStatus": null,
+        })
+    }
+
+    fn chat_channel_id(&self) -> Option<String> {
+        (self.live_id > 0).then(|| format!("N{:x}", self.live_id))
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn handle(state: &Mutex<State>, method: &Method, path: &str) -> hyper::Response<Body> {
+    let mut state = state.lock().unwrap();
+
+    state.requests.push(format!("{method} {path}"));
+
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    let (channel_id, detail) = match (method, segments.as_slice()) {
+        (&Method::GET, ["nng_main", "v1", "user", "getUserStatus"]) => {
+            return respond(
+                StatusCode::OK,
+                json!({
+                    "code": 200,
+                    "message": null,
+                    "content": {
+                        "hasProfile": false,
+                        "userIdHash": null,
+                        "nickname": null,
+                        "profileImageUrl": null,
+                        "penalties": [],
+                        "officialNotiAgree": false,
+                        "officialNotiAgreeUpdatedDate": null,
+                        "verifiedMark": false,
+                        "loggedIn": false,
+                    },
+                }),
+            )
+        }
+        (&Method::GET, ["polling", "v2", "channels", channel_id, "live-status"]) => {
+            (*channel_id, false)
+        }
+        (&Method::GET, ["service", "v2", "channels", channel_id, "live-detail"]) => {
+            (*channel_id, true)
+        }
+        _ => {
+            return respond(
+                StatusCode::NOT_FOUND,
+                json!({ "code": 404, "message": "Not Found" }),
+            )
+        }
+    };
+
+    let Some(channel) = state.channels.get(channel_id) else {
+        return respond(
+            StatusCode::NOT_FOUND,
+            json!({
+                "code": crate::Error::NOT_FOUND_CHANNEL,
+                "message": "Not Found Channel",
+                "content": null,
+            }),
+        );
+    };
+
+    if let Some((status, error)) = &channel.error {
+        return respond(
+            *status,
+            json!({ "code": error.code, "message": error.message, "content": null }),
+        );
+    }
+
+    let content = if detail {
+        channel.live_detail(channel_id)
+    } else {
+        channel.live_status()
+    };
+
+    respond(
+        StatusCode::OK,
+        json!({ "code": 200, "message": null, "content": content }),
+    )
+}
+
+fn respond(status: StatusCode, body: Value) -> hyper::Response<Body> {
+    hyper::Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("status and headers are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        auth::{Auth, AuthError},
+        live::{
+            get_live_detail::GetLiveDetail,
+            get_live_status::{self, GetLiveStatus},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let server = MockServer::start().await.unwrap();
+        let client = server.client_builder().build().unwrap();
+
+        let channel = server.channel("475313e6c26639d5763628313b4c130e");
+
+        let endpoint = GetLiveStatus {
+            channel_id: channel.id(),
+        };
+
+        let live_status = client.execute(endpoint.clone()).await.unwrap();
+        assert_eq!(live_status.status, LiveStatusType::Close);
+
+        channel.go_live("title").set_viewers(10);
+
+        let live_status = client.execute(endpoint.clone()).await.unwrap();
+        assert_eq!(live_status.status, LiveStatusType::Open);
+        assert_eq!(live_status.live_title, "title");
+        assert_eq!(live_status.concurrent_user_count, 10);
+
+        channel.set_title("changed");
+
+        let live_detail = client
+            .execute(GetLiveDetail {
+                channel_id: channel.id(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(live_detail.inherit.live_title, "changed");
+        assert_eq!(live_detail.inherit.live_id, 1);
+
+        channel.end();
+
+        let live_status = client.execute(endpoint.clone()).await.unwrap();
+        assert_eq!(live_status.status, LiveStatusType::Close);
+
+        channel.fail(StatusCode::OK, crate::Error::RATE_LIMITED, None);
+
+        let err = client.execute(endpoint.clone()).await.unwrap_err();
+        assert!(matches!(err, get_live_status::Error::RateLimited(_)));
+
+        channel.recover().remove();
+
+        let err = client.execute(endpoint).await.unwrap_err();
+        assert!(matches!(err, get_live_status::Error::NotFoundChannel(_)));
+
+        assert_eq!(server.requests().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_user_status() {
+        let server = MockServer::start().await.unwrap();
+        let client = server.client_builder().build().unwrap();
+
+        let err = Auth::new("ses", "aut", "jkl")
+            .validate_with(&client)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AuthError::LoggedOut));
+        assert_eq!(server.requests(), ["GET /nng_main/v1/user/getUserStatus"]);
+    }
+}
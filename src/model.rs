@@ -1,5 +1,6 @@
 mod channel;
 mod live;
+mod user;
 
 pub(crate) mod sealed {
     pub use super::live::sealed::*;
@@ -7,3 +8,4 @@ pub(crate) mod sealed {
 
 pub use channel::*;
 pub use live::*;
+pub use user::*;
@@ -33,7 +33,12 @@ pub enum PlayableStatusType {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum UserAdultStatusType {
     Adult,
+    NotAdult,
     NotLoginUser,
+    NotRealNameAuth,
+    /// a status added by Chzzk after this crate was published
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use super::live::UserAdultStatusType;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserStatus {
+    #[serde(rename = "hasProfile")]
+    pub has_profile: bool,
+    #[serde(rename = "userIdHash")]
+    pub user_id_hash: Option<String>,
+    #[serde(rename = "nickname")]
+    pub nickname: Option<String>,
+    #[serde(rename = "profileImageUrl")]
+    pub profile_image_url: Option<String>,
+    #[serde(rename = "verifiedMark", default)]
+    pub verified_mark: bool,
+    #[serde(rename = "userAdultStatus", default)]
+    pub user_adult_status: Option<UserAdultStatusType>,
+    #[serde(rename = "loggedIn")]
+    pub logged_in: bool,
+}
+
+impl UserStatus {
+    pub fn is_adult(&self) -> bool {
+        self.user_adult_status == Some(UserAdultStatusType::Adult)
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::model::LivePollingStatus;
+
+/// `burst` requests per `period`. the bucket starts full.
+///
+/// both `burst` and `period` must be non-zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub burst: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    pub fn per_second(burst: u32) -> Self {
+        Self {
+            burst,
+            period: Duration::from_secs(1),
+        }
+    }
+
+    pub fn per_minute(burst: u32) -> Self {
+        Self {
+            burst,
+            period: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("invalid quota {0:?}, burst and period must be non-zero")]
+pub struct InvalidQuota(pub Quota);
+
+/// Configuration of [`RateLimiter`].
+///
+/// endpoints are identified by their [`Endpoint::PATH`](crate::request::Endpoint::PATH) template.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    /// shared by every request
+    pub global: Option<Quota>,
+    /// used for endpoints which are not listed in `endpoints`
+    pub per_endpoint: Option<Quota>,
+    pub endpoints: HashMap<&'static str, Quota>,
+    /// applies [`RateLimitHint`]s returned by Chzzk, e.g. `callPeriodMilliSecond` of the live status
+    pub respect_hints: bool,
+}
+
+/// Server's hint about how hard a request may be repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimitHint {
+    /// minimum interval between two calls of the same request
+    pub min_interval: Option<Duration>,
+    /// halves the endpoint's quota until a later response is not throttled
+    pub throttled: bool,
+}
+
+impl From<&LivePollingStatus> for RateLimitHint {
+    fn from(status: &LivePollingStatus) -> Self {
+        Self {
+            min_interval: Some(Duration::from_millis(status.call_period_ms))
+                .filter(|x| !x.is_zero()),
+            throttled: status.traffic_throttling > 0,
+        }
+    }
+}
+
+/// Token bucket rate limiter, cloning shares the buckets.
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimit>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    global: Option<TokenBucket>,
+    endpoints: HashMap<&'static str, TokenBucket>,
+    /// request key -> instant until which the request must not be sent again
+    cooldowns: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+    /// fails if a quota of `config` has a zero `burst` or `period`.
+    pub fn new(config: RateLimit) -> Result<Self, InvalidQuota> {
+        let invalid = config
+            .global
+            .iter()
+            .chain(&config.per_endpoint)
+            .chain(config.endpoints.values())
+            .find(|quota| quota.burst == 0 || quota.period.is_zero());
+
+        if let Some(quota) = invalid {
+            return Err(InvalidQuota(*quota));
+        }
+
+        let now = Instant::now();
+
+        let state = State {
+            global: config.global.map(|quota| TokenBucket::new(quota, now)),
+            ..Default::default()
+        };
+
+        Ok(Self {
+            config: Arc::new(config),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    pub fn config(&self) -> &RateLimit {
+        &self.config
+    }
+
+    /// waits until a request to `endpoint` identified by `key` may be sent.
+    pub async fn acquire(&self, endpoint: &'static str, key: &str) {
+        loop {
+            let wait = self.try_acquire(endpoint, key, Instant::now());
+
+            if wait.is_zero() {
+                return;
+            }
+
+            tracing::debug!("rate limited {key}, waiting {wait:?}");
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// takes a token from every bucket if all of them have one,
+    /// otherwise returns how long to wait before trying again.
+    fn try_acquire(&self, endpoint: &'static str, key: &str, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let State {
+            global,
+            endpoints,
+            cooldowns,
+        } = &mut *state;
+
+        let quota = self
+            .config
+            .endpoints
+            .get(endpoint)
+            .or(self.config.per_endpoint.as_ref());
+
+        let mut endpoint_bucket = quota.map(|quota| {
+            endpoints
+                .entry(endpoint)
+                .or_insert_with(|| TokenBucket::new(*quota, now))
+        });
+
+        let cooldown = cooldowns
+            .get(key)
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+
+        let wait = [
+            global.as_mut().map(|x| x.refill(now)),
+            endpoint_bucket.as_mut().map(|x| x.refill(now)),
+            Some(cooldown),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_default();
+
+        if wait.is_zero() {
+            if let Some(global) = global {
+                global.take();
+            }
+            if let Some(endpoint_bucket) = endpoint_bucket {
+                endpoint_bucket.take();
+            }
+            cooldowns.remove(key);
+        }
+
+        wait
+    }
+
+    /// applies a hint received for a request to `endpoint` identified by `key`.
+    pub fn hint(&self, endpoint: &'static str, key: &str, hint: RateLimitHint) {
+        if !self.config.respect_hints {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        state.cooldowns.retain(|_, until| *until > now);
+
+        if let Some(min_interval) = hint.min_interval {
+            state.cooldowns.insert(key.to_owned(), now + min_interval);
+        }
+
+        if let Some(bucket) = state.endpoints.get_mut(endpoint) {
+            bucket.refill(now);
+            bucket.scale = if hint.throttled { 0.5 } else { 1.0 };
+        }
+    }
+}
+
+struct TokenBucket {
+    quota: Quota,
+    scale: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: Quota, now: Instant) -> Self {
+        Self {
+            quota,
+            scale: 1.0,
+            tokens: quota.burst as f64,
+            updated: now,
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        self.quota.burst as f64 / self.quota.period.as_secs_f64() * self.scale
+    }
+
+    /// refills tokens elapsed since the last update and returns how long to wait for a token.
+    fn refill(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.rate()).min(self.quota.burst as f64);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate())
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDPOINT: &str = "/polling/v2/channels/:channel_id/live-status";
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(RateLimit {
+            global: Some(Quota::per_second(2)),
+            ..Default::default()
+        })
+        .unwrap();
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire(ENDPOINT, "a", now).is_zero());
+        assert!(limiter.try_acquire(ENDPOINT, "b", now).is_zero());
+        assert_eq!(
+            limiter.try_acquire(ENDPOINT, "c", now),
+            Duration::from_millis(500)
+        );
+        assert!(limiter
+            .try_acquire(ENDPOINT, "c", now + Duration::from_millis(500))
+            .is_zero());
+    }
+
+    #[test]
+    fn test_invalid_quota() {
+        let quota = Quota::per_second(0);
+
+        let r = RateLimiter::new(RateLimit {
+            endpoints: HashMap::from([(ENDPOINT, quota)]),
+            ..Default::default()
+        });
+        assert_eq!(r.err(), Some(InvalidQuota(quota)));
+
+        let quota = Quota {
+            burst: 1,
+            period: Duration::ZERO,
+        };
+
+        let r = RateLimiter::new(RateLimit {
+            global: Some(quota),
+            ..Default::default()
+        });
+        assert_eq!(r.err(), Some(InvalidQuota(quota)));
+    }
+
+    #[test]
+    fn test_hint() {
+        let limiter = RateLimiter::new(RateLimit {
+            per_endpoint: Some(Quota::per_second(10)),
+            respect_hints: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire(ENDPOINT, "a", now).is_zero());
+
+        limiter.hint(
+            ENDPOINT,
+            "a",
+            RateLimitHint {
+                min_interval: Some(Duration::from_secs(10)),
+                throttled: true,
+            },
+        );
+
+        assert!(limiter.try_acquire(ENDPOINT, "a", Instant::now()) > Duration::from_secs(9));
+        assert!(limiter.try_acquire(ENDPOINT, "b", Instant::now()).is_zero());
+
+        let state = limiter.state.lock().unwrap();
+        assert_eq!(state.endpoints[ENDPOINT].scale, 0.5);
+    }
+}
@@ -1,31 +1,53 @@
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, convert::Infallible, fmt::Debug, time::Duration};
 
 use bytes::Bytes;
-use cookie::Cookie;
-use http::{header, HeaderMap, Method};
+use http::{header, HeaderMap, Method, StatusCode};
 use mime::Mime;
 use reqwest::Response;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize};
 
+pub use crate::auth::Auth;
+use crate::{rate_limit::RateLimitHint, telemetry::RedactedHeaders};
+
+/// API a request is sent to. the client's base url of the API is used unless the request
+/// has its own base url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Api {
+    /// [`CHZZK_API_URL`](crate::CHZZK_API_URL) by default
+    #[default]
+    Chzzk,
+    /// [`GAME_API_URL`](crate::GAME_API_URL) by default
+    Game,
+}
+
+#[derive(Clone)]
 pub struct Request {
-    pub(crate) base_url: &'static str,
+    pub(crate) api: Api,
+    /// overrides the client's base url when set
+    pub(crate) base_url: Option<Cow<'static, str>>,
     pub(crate) method: Method,
     pub(crate) path: Cow<'static, str>,
     pub(crate) headers: Option<HeaderMap>,
     pub(crate) body: Option<RequestBody>,
     pub(crate) query: Option<Cow<'static, str>>,
+    /// overrides the client's timeout when set
+    pub(crate) timeout: Option<Duration>,
 }
 
+#[derive(Clone)]
 pub struct RequestBody {
     pub(crate) content_type: Mime,
     pub(crate) buf: Bytes,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Auth {
-    pub nid_ses: String,
-    pub nid_aut: String,
-    pub nid_jkl: String,
+impl RequestBody {
+    pub fn content_type(&self) -> &Mime {
+        &self.content_type
+    }
+
+    pub fn bytes(&self) -> &Bytes {
+        &self.buf
+    }
 }
 
 pub trait IntoBody {
@@ -36,55 +58,120 @@ impl Debug for Request {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Request")
             .field("method", &self.method)
+            .field("api", &self.api)
             .field("base_url", &self.base_url)
             .field("path", &self.path)
             .field("query", &self.query)
-            .field("headers", &self.headers)
+            .field("headers", &self.headers.as_ref().map(RedactedHeaders))
+            .field("timeout", &self.timeout)
             .finish()
     }
 }
 
 impl Request {
-    fn build(self, token: Option<&Auth>) -> reqwest::RequestBuilder {
-        tracing::debug!("{self:#?}");
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
 
-        let Request {
-            base_url,
-            method,
-            headers,
-            path,
-            body,
-            query,
-        } = self;
+    pub fn api(&self) -> Api {
+        self.api
+    }
+
+    /// `None` if the request is sent to the client's base url
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.headers.as_ref()
+    }
+
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.headers.get_or_insert_with(HeaderMap::new)
+    }
+
+    pub fn body(&self) -> Option<&RequestBody> {
+        self.body.as_ref()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// identifies the request regardless of the base url, `GET /path?query`
+    pub(crate) fn key(&self) -> String {
+        let mut key = format!("{} {}", self.method, self.path);
+
+        if let Some(query) = self.query.as_deref().filter(|x| !x.is_empty()) {
+            key += "?";
+            key += query;
+        }
+
+        key
+    }
+
+    fn url(&self, default_base_url: &str) -> String {
+        let base_url = self.base_url.as_deref().unwrap_or(default_base_url);
 
-        let mut url = String::from(base_url) + &path;
+        let mut url = String::from(base_url.trim_end_matches('/')) + &self.path;
 
-        if let Some(query) = query {
+        if let Some(query) = &self.query {
             if !query.is_empty() {
                 url += "?";
-                url += &query;
+                url += query;
             }
         }
 
-        let mut request = reqwest::Client::new().request(method.clone(), url);
+        url
+    }
+
+    fn build(
+        self,
+        http: &reqwest::Client,
+        default_base_url: &str,
+        token: Option<&Auth>,
+    ) -> reqwest::RequestBuilder {
+        let url = self.url(default_base_url);
+
+        tracing::debug!(
+            method = %self.method,
+            url,
+            headers = ?self.headers.as_ref().map(RedactedHeaders),
+            timeout = ?self.timeout,
+            "send",
+        );
+
+        let Request {
+            api: _,
+            base_url: _,
+            method,
+            headers,
+            path: _,
+            body,
+            query: _,
+            timeout,
+        } = self;
+
+        let mut request = http.request(method.clone(), url);
 
         if let Some(headers) = headers {
             request = request.headers(headers);
         }
 
-        if let Some(Auth {
-            nid_ses,
-            nid_aut,
-            nid_jkl,
-        }) = token
-        {
-            let cookie = Cookie::from_iter([
-                ("NID_SES", &**nid_ses),
-                ("NID_AUT", &**nid_aut),
-                ("NID_JKL", &**nid_jkl),
-            ]);
-
-            request = request.header(header::COOKIE, cookie.into_str());
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
+        if let Some(token) = token {
+            request = request.header(header::COOKIE, token.to_cookie());
         }
 
         if let Some(RequestBody { content_type, buf }) = body {
@@ -96,9 +183,120 @@ impl Request {
         request
     }
 
-    pub(crate) async fn send(self, token: impl Into<Option<&Auth>>) -> reqwest::Result<Response> {
-        self.build(token.into()).send().await
+    /// Converts into an [`http::Request`] to be sent by any HTTP client.
+    ///
+    /// `default_base_url`, the base url of [`Request::api`], is used unless the request has
+    /// its own base url, and `token` is applied as the `Cookie` header. the timeout of the
+    /// request is not carried over.
+    /// decode the response with [`Endpoint::decode_http`].
+    pub fn into_http(
+        self,
+        default_base_url: &str,
+        token: Option<&Auth>,
+    ) -> Result<http::Request<Bytes>, http::Error> {
+        let url = self.url(default_base_url);
+
+        let Request {
+            method,
+            headers,
+            body,
+            ..
+        } = self;
+
+        let mut request = http::Request::builder().method(method).uri(url);
+
+        if let (Some(headers), Some(x)) = (headers, request.headers_mut()) {
+            x.extend(headers);
+        }
+
+        if let Some(token) = token {
+            request = request.header(header::COOKIE, token.to_cookie());
+        }
+
+        match body {
+            Some(RequestBody { content_type, buf }) => request
+                .header(header::CONTENT_TYPE, content_type.as_ref())
+                .body(buf),
+            None => request.body(Bytes::new()),
+        }
+    }
+
+    pub(crate) async fn send_with(
+        self,
+        http: &reqwest::Client,
+        default_base_url: &str,
+        token: Option<&Auth>,
+    ) -> reqwest::Result<Response> {
+        self.build(http, default_base_url, token).send().await
+    }
+
+    /// sends with a HTTP client of its own, used by the `send` methods of endpoints.
+    ///
+    /// pooled connections belong to the runtime which opened them, so they are not shared
+    /// between calls which may run on different runtimes. use a [`Client`](crate::Client) to pool them.
+    pub(crate) async fn execute<E: Endpoint>(
+        self,
+        default_base_url: &str,
+        token: Option<&Auth>,
+    ) -> Result<E::Output, E::Error> {
+        let resp = self
+            .send_with(&reqwest::Client::new(), default_base_url, token)
+            .await?;
+
+        let status = resp.status();
+        let bytes = resp.bytes().await?;
+
+        decode_response::<E>(status, bytes)
+    }
+}
+
+pub(crate) async fn into_http_response(resp: Response) -> reqwest::Result<http::Response<Bytes>> {
+    let mut builder = http::Response::builder()
+        .status(resp.status())
+        .version(resp.version());
+
+    if let Some(headers) = builder.headers_mut() {
+        *headers = resp.headers().clone();
+    }
+
+    let body = resp.bytes().await?;
+
+    Ok(builder
+        .body(body)
+        .expect("status and headers are already valid"))
+}
+
+/// Decodes the body of a response, or builds the endpoint's error when the
+/// HTTP status or the `code` of the Chzzk envelope is not successful.
+pub fn decode_response<E: Endpoint>(
+    status: StatusCode,
+    bytes: Bytes,
+) -> Result<E::Output, E::Error> {
+    let envelope = crate::Error::from_slice(&bytes);
+
+    decode_parsed_response::<E>(status, bytes, envelope.as_ref())
+}
+
+/// [`decode_response`] with the envelope already parsed from `bytes`.
+pub(crate) fn decode_parsed_response<E: Endpoint>(
+    status: StatusCode,
+    bytes: Bytes,
+    envelope: Option<&crate::Error>,
+) -> Result<E::Output, E::Error> {
+    let succeeded =
+        status == StatusCode::OK && envelope.is_none_or(|error| error.code == crate::Error::OK);
+
+    if !succeeded {
+        return Err(E::Error::from_parsed_response(
+            status,
+            bytes,
+            envelope.cloned(),
+        ));
     }
+
+    let r = E::decode(bytes)?;
+
+    Ok(r)
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,8 +335,339 @@ pub enum DecodeError {
     DeserializeJson(#[from] serde_json::Error),
 }
 
+impl From<Infallible> for DecodeError {
+    fn from(x: Infallible) -> Self {
+        match x {}
+    }
+}
+
 pub trait Decode: Sized {
     type Output;
 
     fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError>;
 }
+
+/// Decodes a [`ChzzkResponse`] whose `content` is `S` and converts it into the public model `T`.
+pub(crate) fn decode_content<S, T>(bytes: &Bytes) -> Result<T, DecodeError>
+where
+    S: DeserializeOwned + Debug,
+    T: TryFrom<S>,
+    DecodeError: From<T::Error>,
+{
+    let deserialized: ChzzkResponse<S> = serde_json::from_slice(bytes)?;
+
+    Ok(deserialized.content.try_into()?)
+}
+
+/// Error type of an [`Endpoint`], generated by `expand_error!`.
+pub trait EndpointError:
+    From<EncodeError>
+    + From<DecodeError>
+    + From<reqwest::Error>
+    + std::error::Error
+    + Send
+    + Sync
+    + 'static
+{
+    fn is_retryable(&self) -> bool;
+
+    /// builds the error from a response whose status is not `200 OK`.
+    fn from_response(status: StatusCode, body: Bytes) -> Self {
+        let envelope = crate::Error::from_slice(&body);
+
+        Self::from_parsed_response(status, body, envelope)
+    }
+
+    /// [`EndpointError::from_response`] with the envelope already parsed from `body`.
+    fn from_parsed_response(
+        status: StatusCode,
+        body: Bytes,
+        envelope: Option<crate::Error>,
+    ) -> Self;
+
+    /// builds the error from the error envelope of a response.
+    fn from_envelope(status: StatusCode, error: crate::Error) -> Self;
+}
+
+/// A Chzzk API endpoint.
+///
+/// ties [`Encode`], [`Decode`] and the endpoint's error type together,
+/// so any endpoint can be executed by [`Client::execute`](crate::Client::execute).
+pub trait Endpoint: Encode + Decode {
+    type Error: EndpointError;
+
+    /// name of the endpoint in tracing spans and metrics, e.g. `GetLiveStatus`
+    const NAME: &'static str;
+
+    const METHOD: Method;
+
+    /// path template expanded by `serde_path`, e.g. `/polling/v2/channels/:channel_id/live-status`
+    const PATH: &'static str;
+
+    /// rate limit hint of Chzzk carried by the decoded output
+    fn rate_limit_hint(_output: &Self::Output) -> Option<RateLimitHint> {
+        None
+    }
+
+    /// decodes a response received by any HTTP client, see [`Request::into_http`].
+    fn decode_http(response: http::Response<Bytes>) -> Result<Self::Output, Self::Error> {
+        let (parts, body) = response.into_parts();
+
+        decode_response::<Self>(parts.status, body)
+    }
+}
+
+/// Response handling of an [`Endpoint`], detached from the endpoint's type so it is `'static`
+/// even when the endpoint borrows its parameters.
+pub(crate) struct Decoder<O, Er> {
+    pub(crate) name: &'static str,
+    pub(crate) path: &'static str,
+    pub(crate) decode: fn(StatusCode, Bytes, Option<&crate::Error>) -> Result<O, Er>,
+    pub(crate) rate_limit_hint: fn(&O) -> Option<RateLimitHint>,
+}
+
+impl<O, Er> Clone for Decoder<O, Er> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<O, Er> Copy for Decoder<O, Er> {}
+
+impl<O, Er: EndpointError> Decoder<O, Er> {
+    pub(crate) fn of<E>() -> Self
+    where
+        E: Endpoint<Output = O, Error = Er>,
+    {
+        Self {
+            name: E::NAME,
+            path: E::PATH,
+            decode: decode_parsed_response::<E>,
+            rate_limit_hint: E::rate_limit_hint,
+        }
+    }
+}
+
+/// Sends `endpoint` to `base_url` instead of the client's base url.
+///
+/// path templates of `endpoint` are still applied on top of `base_url`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct WithBaseUrl<'a, E> {
+    pub base_url: &'a str,
+    pub endpoint: E,
+}
+
+impl<'a, E: Encode> Encode for WithBaseUrl<'a, E> {
+    fn encode_ref(&self) -> Result<Request, EncodeError> {
+        let request = self.endpoint.encode_ref()?;
+
+        Ok(Request {
+            base_url: Some(self.base_url.to_owned().into()),
+            ..request
+        })
+    }
+}
+
+impl<'a, E: Decode> Decode for WithBaseUrl<'a, E> {
+    type Output = E::Output;
+
+    fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError> {
+        E::decode(bytes)
+    }
+}
+
+impl<'a, E: Endpoint> Endpoint for WithBaseUrl<'a, E> {
+    type Error = E::Error;
+
+    const NAME: &'static str = E::NAME;
+    const METHOD: Method = E::METHOD;
+    const PATH: &'static str = E::PATH;
+
+    fn rate_limit_hint(output: &Self::Output) -> Option<RateLimitHint> {
+        E::rate_limit_hint(output)
+    }
+}
+
+/// Sends `endpoint` with `timeout` instead of the client's timeout.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct WithTimeout<E> {
+    pub timeout: Duration,
+    pub endpoint: E,
+}
+
+impl<E: Encode> Encode for WithTimeout<E> {
+    fn encode_ref(&self) -> Result<Request, EncodeError> {
+        let request = self.endpoint.encode_ref()?;
+
+        Ok(Request {
+            timeout: Some(self.timeout),
+            ..request
+        })
+    }
+}
+
+impl<E: Decode> Decode for WithTimeout<E> {
+    type Output = E::Output;
+
+    fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError> {
+        E::decode(bytes)
+    }
+}
+
+impl<E: Endpoint> Endpoint for WithTimeout<E> {
+    type Error = E::Error;
+
+    const NAME: &'static str = E::NAME;
+    const METHOD: Method = E::METHOD;
+    const PATH: &'static str = E::PATH;
+
+    fn rate_limit_hint(output: &Self::Output) -> Option<RateLimitHint> {
+        E::rate_limit_hint(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        live::{get_live_detail, get_live_status::GetLiveStatus},
+        CHZZK_API_URL,
+    };
+
+    /// `send_blocking` and then `send` from another runtime, while the first one is idle
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_execute_across_runtimes() {
+        use crate::fixtures::{HttpServer, LIVE_STATUS};
+
+        let server = HttpServer::start(|_| (StatusCode::OK, LIVE_STATUS));
+        let base_url = server.base_url();
+
+        let execute = || {
+            GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            }
+            .encode()
+            .unwrap()
+            .execute::<GetLiveStatus>(&base_url, None)
+        };
+
+        crate::blocking::block_on(execute()).unwrap();
+
+        for _ in 0..2 {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+
+            let live_status = runtime
+                .block_on(async { tokio::time::timeout(Duration::from_secs(3), execute()).await })
+                .expect("hung on a connection of another runtime")
+                .unwrap();
+
+            assert_eq!(live_status.live_title, "title");
+        }
+    }
+
+    #[test]
+    fn test_base_url() {
+        let http = reqwest::Client::new();
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+
+        let request = endpoint
+            .encode_ref()
+            .unwrap()
+            .build(&http, "http://127.0.0.1:8080/", None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "http://127.0.0.1:8080/polling/v2/channels/475313e6c26639d5763628313b4c130e/live-status"
+        );
+
+        let request = WithBaseUrl {
+            base_url: "http://localhost/proxy",
+            endpoint,
+        }
+        .encode()
+        .unwrap()
+        .build(&http, CHZZK_API_URL, None)
+        .build()
+        .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost/proxy/polling/v2/channels/475313e6c26639d5763628313b4c130e/live-status"
+        );
+    }
+
+    #[test]
+    fn test_error_envelope() {
+        type E<'a> = get_live_detail::GetLiveDetail<'a>;
+
+        let body =
+            Bytes::from_static(br#"{"code":404,"message":"Not Found Channel","content":null}"#);
+        let err = decode_response::<E>(StatusCode::OK, body).unwrap_err();
+        assert!(matches!(
+            err,
+            get_live_detail::Error::NotFoundChannel(crate::Error { code: 404, .. })
+        ));
+
+        let body = Bytes::from_static(br#"{"code":401,"message":null,"content":null}"#);
+        let err = decode_response::<E>(StatusCode::UNAUTHORIZED, body).unwrap_err();
+        assert!(matches!(err, get_live_detail::Error::AuthRequired(_)));
+
+        let body = Bytes::from_static(br#"{"code":9999,"message":"unknown","content":null}"#);
+        let err = decode_response::<E>(StatusCode::BAD_REQUEST, body).unwrap_err();
+        assert!(matches!(
+            err,
+            get_live_detail::Error::Chzzk(StatusCode::BAD_REQUEST, crate::Error { code: 9999, .. })
+        ));
+
+        let body = Bytes::from_static(b"<html>bad gateway</html>");
+        let err = decode_response::<E>(StatusCode::BAD_GATEWAY, body).unwrap_err();
+        assert!(matches!(
+            err,
+            get_live_detail::Error::Undefined(StatusCode::BAD_GATEWAY, _)
+        ));
+    }
+
+    #[test]
+    fn test_http() {
+        let auth = Auth::new("ses", "aut", "jkl");
+
+        let request = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        }
+        .encode()
+        .unwrap()
+        .into_http(CHZZK_API_URL, Some(&auth))
+        .unwrap();
+
+        assert_eq!(request.method(), Method::GET);
+        assert_eq!(
+            request.uri(),
+            "https://api.chzzk.naver.com/polling/v2/channels/475313e6c26639d5763628313b4c130e/live-status"
+        );
+        let cookie = request.headers()[header::COOKIE].to_str().unwrap();
+        assert!(["NID_SES=ses", "NID_AUT=aut", "NID_JKL=jkl"]
+            .into_iter()
+            .all(|x| cookie.contains(x)));
+
+        let response = http::Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Bytes::from_static(
+                br#"{"code":404,"message":"Not Found Channel","content":null}"#,
+            ))
+            .unwrap();
+
+        let err = GetLiveStatus::decode_http(response).unwrap_err();
+
+        assert!(matches!(
+            err,
+            crate::live::get_live_status::Error::NotFoundChannel(_)
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+
+/// Decoded output of an endpoint along with the response it was decoded from.
+///
+/// returned by [`Client::execute_response`](crate::Client::execute_response).
+/// `code` and `message` are the ones of the [`ChzzkResponse`](crate::request::ChzzkResponse) envelope.
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub code: i64,
+    pub message: Option<String>,
+    /// raw body, before decoding
+    pub body: Bytes,
+    pub content: T,
+}
+
+impl<T> Response<T> {
+    /// `envelope` is the one parsed from `body`.
+    pub(crate) fn new(
+        status: StatusCode,
+        headers: HeaderMap,
+        body: Bytes,
+        envelope: Option<crate::Error>,
+        content: T,
+    ) -> Self {
+        let (code, message) = match envelope {
+            Some(envelope) => (envelope.code, envelope.message),
+            None => (crate::Error::OK, None),
+        };
+
+        Self {
+            status,
+            headers,
+            code,
+            message,
+            body,
+            content,
+        }
+    }
+
+    pub fn into_content(self) -> T {
+        self.content
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Response<U> {
+        Response {
+            status: self.status,
+            headers: self.headers,
+            code: self.code,
+            message: self.message,
+            body: self.body,
+            content: f(self.content),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header;
+
+    use super::*;
+    use crate::{
+        fixtures::{Respond, LIVE_STATUS},
+        live::get_live_status::GetLiveStatus,
+        Client,
+    };
+
+    #[tokio::test]
+    async fn test_response() {
+        let client = Client::builder()
+            .middleware(Respond::ok(LIVE_STATUS).header(header::ETAG, "\"1\""))
+            .build()
+            .unwrap();
+
+        let response = client
+            .execute_response(GetLiveStatus {
+                channel_id: "475313e6c26639d5763628313b4c130e",
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.code, crate::Error::OK);
+        assert_eq!(response.message, None);
+        assert_eq!(response.headers[header::ETAG], "\"1\"");
+        assert_eq!(response.body, LIVE_STATUS.as_bytes());
+        assert_eq!(response.content.live_title, "title");
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use http::{header, HeaderMap, StatusCode};
+
+/// Retry policy of [`Client`](crate::Client). disabled unless set with
+/// [`ClientBuilder::retry`](crate::client::ClientBuilder::retry).
+///
+/// only errors whose `is_retryable()` returns `true` are retried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// including the first attempt
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// randomizes each backoff between half and the full computed delay
+    pub jitter: bool,
+    /// waits for the `Retry-After` of the response instead of the computed backoff,
+    /// capped to `max_backoff`
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// a policy which never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// delay before the next attempt, `attempt` is the number of the failed attempt starting at 1.
+    pub fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after.filter(|_| self.respect_retry_after) {
+            return retry_after.min(self.max_backoff);
+        }
+
+        let exp = self
+            .multiplier
+            .powi(attempt.saturating_sub(1) as i32)
+            .max(1.0);
+        let backoff = Duration::from_secs_f64(
+            (self.initial_backoff.as_secs_f64() * exp).min(self.max_backoff.as_secs_f64()),
+        );
+
+        if self.jitter {
+            backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// parses `Retry-After` given in delay-seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// whether an envelope `code`, which mirrors the HTTP statuses, is retryable
+pub(crate) fn is_retryable_code(code: i64) -> bool {
+    u16::try_from(code)
+        .ok()
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .is_some_and(is_retryable_status)
+}
+
+pub(crate) fn is_retryable_reqwest(error: &reqwest::Error) -> bool {
+    error.is_timeout()
+        || error.is_connect()
+        || error.is_request()
+        || error.status().is_some_and(is_retryable_status)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::HeaderValue;
+
+    use super::*;
+    use crate::{
+        live::get_live_status,
+        request::{decode_response, EndpointError},
+    };
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(1, None), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2, None), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3, None), Duration::from_millis(800));
+        assert_eq!(policy.backoff(30, None), Duration::from_secs(10));
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(86400))),
+            Duration::from_secs(10)
+        );
+
+        let policy = RetryPolicy::default();
+
+        for attempt in 1..10 {
+            let backoff = policy.backoff(attempt, None);
+            let max = RetryPolicy {
+                jitter: false,
+                ..policy.clone()
+            }
+            .backoff(attempt, None);
+
+            assert!(backoff >= max / 2 && backoff <= max);
+        }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(
+            header::RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        type E<'a> = get_live_status::GetLiveStatus<'a>;
+
+        let err = get_live_status::Error::from_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Bytes::from_static(b"service unavailable"),
+        );
+        assert!(err.is_retryable());
+
+        let body = Bytes::from_static(br#"{"code":429,"message":null,"content":null}"#);
+        let err = decode_response::<E>(StatusCode::OK, body).unwrap_err();
+        assert!(err.is_retryable());
+
+        let body = Bytes::from_static(br#"{"code":503,"message":null,"content":null}"#);
+        let err = decode_response::<E>(StatusCode::OK, body).unwrap_err();
+        assert!(matches!(
+            err,
+            get_live_status::Error::Chzzk(StatusCode::OK, _)
+        ));
+        assert!(err.is_retryable());
+
+        let body = Bytes::from_static(br#"{"code":400,"message":null,"content":null}"#);
+        let err = decode_response::<E>(StatusCode::OK, body).unwrap_err();
+        assert!(!err.is_retryable());
+
+        let body = Bytes::from_static(br#"{"code":404,"message":null,"content":null}"#);
+        let err = decode_response::<E>(StatusCode::NOT_FOUND, body).unwrap_err();
+        assert!(!err.is_retryable());
+
+        let err = decode_response::<E>(StatusCode::OK, Bytes::from_static(b"{}")).unwrap_err();
+        assert!(!err.is_retryable());
+    }
+}
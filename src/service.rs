@@ -0,0 +1,92 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tower_service::Service;
+
+use crate::{
+    request::{Decoder, EncodeError, Endpoint, EndpointError, Request},
+    response::Response,
+    Client,
+};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// `tower::Service` over any [`Endpoint`].
+///
+/// the endpoint is encoded in [`Service::call`], so the returned future is `'static`
+/// even when the endpoint borrows its parameters.
+impl<E> Service<E> for Client
+where
+    E: Endpoint,
+    E::Output: Send + 'static,
+{
+    type Response = E::Output;
+    type Error = E::Error;
+    type Future = BoxFuture<Result<E::Output, E::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, endpoint: E) -> Self::Future {
+        call(self.clone(), endpoint.encode(), Decoder::of::<E>())
+    }
+}
+
+fn call<O, Er>(
+    client: Client,
+    request: Result<Request, EncodeError>,
+    decoder: Decoder<O, Er>,
+) -> BoxFuture<Result<O, Er>>
+where
+    O: Send + 'static,
+    Er: EndpointError,
+{
+    Box::pin(async move {
+        client
+            .execute_request(request?, decoder)
+            .await
+            .map(Response::into_content)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::{Respond, NOT_FOUND_CHANNEL},
+        live::{get_live_status, get_live_status::GetLiveStatus},
+    };
+
+    #[tokio::test]
+    async fn test_service() {
+        let mut client = Client::builder()
+            .middleware(Respond::new(StatusCode::NOT_FOUND, NOT_FOUND_CHANNEL))
+            .build()
+            .unwrap();
+
+        let channel_id = String::from("475313e6c26639d5763628313b4c130e");
+
+        poll_fn(|cx| Service::<GetLiveStatus>::poll_ready(&mut client, cx))
+            .await
+            .unwrap();
+
+        let future = client.call(GetLiveStatus {
+            channel_id: &channel_id,
+        });
+
+        // the future doesn't borrow the endpoint
+        drop(channel_id);
+
+        let err = tokio::spawn(future).await.unwrap().unwrap_err();
+
+        assert!(matches!(err, get_live_status::Error::NotFoundChannel(_)));
+    }
+}
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, future::Future, sync::Mutex};
+
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode, Version};
+use tokio::sync::broadcast;
+
+/// Coalesces identical concurrent requests into a single call.
+///
+/// the first caller of a key sends the request and shares the response with every caller
+/// which arrived while it was in flight. if the first caller fails or is cancelled,
+/// the others send their own request.
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    in_flight: Mutex<HashMap<String, broadcast::Sender<Option<Shared>>>>,
+}
+
+#[derive(Clone)]
+struct Shared {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl Shared {
+    fn new(response: &http::Response<Bytes>) -> Self {
+        Self {
+            status: response.status(),
+            version: response.version(),
+            headers: response.headers().clone(),
+            body: response.body().clone(),
+        }
+    }
+
+    fn into_response(self) -> http::Response<Bytes> {
+        let mut response = http::Response::new(self.body);
+
+        *response.status_mut() = self.status;
+        *response.version_mut() = self.version;
+        *response.headers_mut() = self.headers;
+
+        response
+    }
+}
+
+/// publishes the response of the first caller when dropped
+struct Flight<'a> {
+    single_flight: &'a SingleFlight,
+    key: String,
+    tx: broadcast::Sender<Option<Shared>>,
+    response: Option<Shared>,
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.single_flight.in_flight.lock().unwrap();
+
+        in_flight.remove(&self.key);
+
+        // receivers are subscribed under the same lock, so none of them can miss the response
+        self.tx.send(self.response.take()).ok();
+    }
+}
+
+enum Role<'a> {
+    Leader(Flight<'a>),
+    Follower(broadcast::Receiver<Option<Shared>>),
+}
+
+impl SingleFlight {
+    pub(crate) async fn run<F, Fut>(
+        &self,
+        key: String,
+        send: F,
+    ) -> reqwest::Result<http::Response<Bytes>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = reqwest::Result<http::Response<Bytes>>>,
+    {
+        let role = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+
+            match in_flight.get(&key) {
+                Some(tx) => {
+                    tracing::debug!("coalesce {key}");
+
+                    Role::Follower(tx.subscribe())
+                }
+                None => {
+                    let (tx, _) = broadcast::channel(1);
+
+                    in_flight.insert(key.clone(), tx.clone());
+
+                    Role::Leader(Flight {
+                        single_flight: self,
+                        key,
+                        tx,
+                        response: None,
+                    })
+                }
+            }
+        };
+
+        match role {
+            Role::Leader(mut flight) => {
+                let r = send().await;
+
+                flight.response = r.as_ref().ok().map(Shared::new);
+
+                r
+            }
+            Role::Follower(mut rx) => match rx.recv().await {
+                Ok(Some(shared)) => Ok(shared.into_response()),
+                _ => send().await,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use futures_util::future::join_all;
+
+    use super::*;
+    use crate::{
+        fixtures::{HttpServer, LIVE_STATUS},
+        live::get_live_status::GetLiveStatus,
+        Client,
+    };
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        let server = HttpServer::start(|_| {
+            thread::sleep(Duration::from_millis(100));
+
+            (StatusCode::OK, LIVE_STATUS)
+        });
+
+        let client = Client::builder()
+            .base_url(server.base_url())
+            .no_proxy()
+            .single_flight(true)
+            .build()
+            .unwrap();
+
+        let endpoint = GetLiveStatus {
+            channel_id: "475313e6c26639d5763628313b4c130e",
+        };
+
+        let results = join_all((0..5).map(|_| client.execute(endpoint.clone()))).await;
+
+        for r in results {
+            assert_eq!(r.unwrap().live_title, "title");
+        }
+
+        assert_eq!(server.requests().len(), 1);
+
+        // not in flight anymore
+        client.execute(endpoint).await.unwrap();
+
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,158 @@
+//! Tracing spans and metrics of requests.
+//!
+//! every call of [`Client`](crate::Client) runs in a `chzzk_request` span carrying the
+//! endpoint name, method, path, HTTP status, Chzzk code and latency. with the `metrics`
+//! feature, the following metrics are recorded through the `metrics` facade:
+//!
+//! - `chzzk_requests_total` counter, labeled by `endpoint` and `status`
+//! - `chzzk_request_duration_seconds` histogram, labeled by `endpoint`
+//!
+//! the values of the `NID_SES`, `NID_AUT` and `NID_JKL` cookies are never logged.
+
+use std::{
+    fmt::{self, Debug, Display},
+    time::Duration,
+};
+
+use http::{header, HeaderMap, StatusCode};
+use tracing::{field, Span};
+
+use crate::{auth::COOKIE_NAMES, request::Request};
+
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// replaces the values of session cookies in a `Cookie` or `Set-Cookie` header value.
+pub(crate) fn redact_cookie(value: &str) -> String {
+    value
+        .split(';')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if COOKIE_NAMES.contains(&name.trim()) => {
+                format!("{name}={REDACTED}")
+            }
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// `Debug` of headers whose session cookies are redacted
+pub(crate) struct RedactedHeaders<'a>(pub(crate) &'a HeaderMap);
+
+impl Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(name, value)| {
+                let value = value.to_str().unwrap_or("<binary>");
+
+                let value = match *name {
+                    header::COOKIE | header::SET_COOKIE => redact_cookie(value),
+                    _ => value.to_owned(),
+                };
+
+                (name, value)
+            }))
+            .finish()
+    }
+}
+
+pub(crate) fn span(endpoint: &'static str, request: &Request) -> Span {
+    tracing::info_span!(
+        "chzzk_request",
+        endpoint,
+        method = %request.method(),
+        path = request.path(),
+        status = field::Empty,
+        code = field::Empty,
+        latency_ms = field::Empty,
+    )
+}
+
+/// Outcome of a call, recorded into its span and metrics.
+#[derive(Default)]
+pub(crate) struct Call {
+    status: Option<StatusCode>,
+    code: Option<i64>,
+}
+
+impl Call {
+    pub(crate) fn response(&mut self, status: StatusCode, envelope: Option<&crate::Error>) {
+        self.status = Some(status);
+        self.code = envelope.map(|error| error.code);
+    }
+
+    pub(crate) fn cached(&mut self) {
+        self.status = Some(StatusCode::OK);
+        self.code = None;
+    }
+
+    pub(crate) fn finish(
+        self,
+        span: &Span,
+        endpoint: &'static str,
+        latency: Duration,
+        error: Option<&dyn Display>,
+    ) {
+        if let Some(status) = self.status {
+            span.record("status", status.as_u16());
+        }
+
+        if let Some(code) = self.code {
+            span.record("code", code);
+        }
+
+        span.record("latency_ms", latency.as_millis() as u64);
+
+        span.in_scope(|| match error {
+            Some(err) => tracing::debug!(%err, "failed"),
+            None => tracing::debug!("succeeded"),
+        });
+
+        #[cfg(feature = "metrics")]
+        {
+            let status = match self.status {
+                Some(status) => status.as_str().to_owned(),
+                None => "error".to_owned(),
+            };
+
+            metrics::counter!("chzzk_requests_total", "endpoint" => endpoint, "status" => status)
+                .increment(1);
+            metrics::histogram!("chzzk_request_duration_seconds", "endpoint" => endpoint)
+                .record(latency.as_secs_f64());
+        }
+
+        #[cfg(not(feature = "metrics"))]
+        let _ = endpoint;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact_cookie("NID_SES=ses; a=b; NID_AUT=aut;NID_JKL=jkl"),
+            "NID_SES=[redacted]; a=b; NID_AUT=[redacted];NID_JKL=[redacted]"
+        );
+        assert_eq!(
+            redact_cookie("NID_SES=ses; Path=/; Domain=.naver.com"),
+            "NID_SES=[redacted]; Path=/; Domain=.naver.com"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("NID_SES=ses; NID_AUT=aut"),
+        );
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("chzzk-rs"));
+
+        let debug = format!("{:?}", RedactedHeaders(&headers));
+
+        assert!(!debug.contains("ses;"));
+        assert!(!debug.contains("aut"));
+        assert!(debug.contains("chzzk-rs"));
+    }
+}
@@ -0,0 +1,114 @@
+use bytes::Bytes;
+use http::Method;
+
+use crate::{
+    error::expand_error,
+    model,
+    request::{
+        decode_content, Api, Auth, Decode, DecodeError, Encode, EncodeError, Endpoint, Request,
+    },
+    GAME_API_URL,
+};
+
+expand_error![];
+
+/// Status of the user logged in with the [`Auth`] of the request.
+///
+/// sent to the game API, see [`Api::Game`]. responds `loggedIn: false` without a valid session.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct GetUserStatus;
+
+impl GetUserStatus {
+    pub async fn send(&self, token: impl Into<Option<&Auth>>) -> Result<model::UserStatus, Error> {
+        self.encode_ref()?
+            .execute::<Self>(GAME_API_URL, token.into())
+            .await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn send_blocking<'t>(
+        &self,
+        token: impl Into<Option<&'t Auth>>,
+    ) -> Result<model::UserStatus, Error> {
+        crate::blocking::block_on(self.send(token))
+    }
+}
+
+impl Endpoint for GetUserStatus {
+    type Error = Error;
+
+    const NAME: &'static str = "GetUserStatus";
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/nng_main/v1/user/getUserStatus";
+}
+
+impl Encode for GetUserStatus {
+    fn encode_ref(&self) -> Result<Request, EncodeError> {
+        Ok(Request {
+            api: Api::Game,
+            base_url: None,
+            method: Self::METHOD,
+            path: Self::PATH.into(),
+            headers: None,
+            body: None,
+            query: None,
+            timeout: None,
+        })
+    }
+}
+
+impl Decode for GetUserStatus {
+    type Output = model::UserStatus;
+
+    fn decode(bytes: Bytes) -> Result<Self::Output, DecodeError> {
+        decode_content::<model::UserStatus, _>(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::UserAdultStatusType;
+
+    #[test]
+    fn test_decode() {
+        let bytes = Bytes::from_static(br#"{"code":200,"message":null,"content":{"hasProfile":true,"userIdHash":"0123456789abcdef0123456789abcdef","nickname":"nickname","profileImageUrl":"https://example.com/profile.png","penalties":[],"officialNotiAgree":false,"officialNotiAgreeUpdatedDate":null,"verifiedMark":true,"userAdultStatus":"ADULT","loggedIn":true}}"#);
+
+        let user_status = GetUserStatus::decode(bytes).unwrap();
+
+        assert!(user_status.logged_in);
+        assert!(user_status.verified_mark);
+        assert!(user_status.is_adult());
+        assert_eq!(user_status.nickname.as_deref(), Some("nickname"));
+
+        let bytes = Bytes::from_static(br#"{"code":200,"message":null,"content":{"hasProfile":true,"userIdHash":"0123456789abcdef0123456789abcdef","nickname":"nickname","profileImageUrl":null,"penalties":[],"officialNotiAgree":false,"officialNotiAgreeUpdatedDate":null,"verifiedMark":false,"userAdultStatus":"NOT_REAL_NAME_AUTH","loggedIn":true}}"#);
+
+        let user_status = GetUserStatus::decode(bytes).unwrap();
+
+        assert!(user_status.logged_in);
+        assert!(!user_status.is_adult());
+        assert_eq!(
+            user_status.user_adult_status,
+            Some(UserAdultStatusType::NotRealNameAuth)
+        );
+
+        let bytes = Bytes::from_static(br#"{"code":200,"message":null,"content":{"hasProfile":true,"userIdHash":"0123456789abcdef0123456789abcdef","nickname":"nickname","profileImageUrl":null,"penalties":[],"officialNotiAgree":false,"officialNotiAgreeUpdatedDate":null,"verifiedMark":false,"userAdultStatus":"SOMETHING_NEW","loggedIn":true}}"#);
+
+        let user_status = GetUserStatus::decode(bytes).unwrap();
+
+        assert!(!user_status.is_adult());
+        assert_eq!(
+            user_status.user_adult_status,
+            Some(UserAdultStatusType::Unknown)
+        );
+
+        let bytes = Bytes::from_static(br#"{"code":200,"message":null,"content":{"hasProfile":false,"userIdHash":null,"nickname":null,"profileImageUrl":null,"penalties":[],"officialNotiAgree":false,"officialNotiAgreeUpdatedDate":null,"verifiedMark":false,"loggedIn":false}}"#);
+
+        let user_status = GetUserStatus::decode(bytes).unwrap();
+
+        assert!(!user_status.logged_in);
+        assert!(!user_status.is_adult());
+        assert_eq!(user_status.user_id_hash, None);
+    }
+}